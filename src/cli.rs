@@ -1,6 +1,14 @@
 //! Setup and configuration of the command-line interface
 
-use clap::{Parser, Subcommand};
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use crate::core::api::{DEFAULT_FETCH_JOBS, Progress, ListSort};
+use crate::core::report::{Condition, SortKey};
+use crate::core::manifest::{ManifestFormat, ImportSource};
+use crate::utils::parse_duration;
+
+use clap::{Parser, Subcommand, ColorChoice};
 
 /// Handles parsing of command-line arguments
 #[derive(Parser)]
@@ -8,7 +16,10 @@ use clap::{Parser, Subcommand};
 #[command(propagate_version = true)]
 pub struct Cli {
     #[command(subcommand)]
-    command: Commands
+    command: Commands,
+    /// When to use colors, `auto` uses them only on terminals and respects `NO_COLOR`
+    #[arg(long, global = true, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice
 }
 
 impl Cli {
@@ -16,6 +27,10 @@ impl Cli {
     pub fn get_command(&self) -> &Commands {
         &self.command
     }
+
+    pub fn get_color(&self) -> ColorChoice {
+        self.color
+    }
 }
 
 /// List of available commands and options
@@ -41,7 +56,20 @@ pub enum Commands {
         quiet: bool
     },
     /// Print the list of tracked repositories
-    List,
+    List {
+        /// Order of the repositories, shows the time of their last activity
+        /// (commit, local modification or fetch) [default: the order they are tracked in]
+        #[arg(long, value_name = "KEY")]
+        sort: Option<ListSort>,
+        /// Print only the repositories without any activity for longer
+        /// than the given duration (e.g. 90d), shows the time of their last activity
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        stale: Option<Duration>,
+        /// Maximum number of repositories read at once when showing
+        /// their activity [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>
+    },
     /// Add specified repositories for tracking
     Add {
         #[arg(required = true)]
@@ -64,13 +92,283 @@ pub enum Commands {
         #[arg(short, long, group = "repositories")]
         #[arg(default_value_t = false)]
         all: bool,
-        /// Print only the output of `git status -s`
-        #[arg(short, long, group = "output")]
+        /// Print only the changes in the working tree, same as
+        /// --only dirty,stashed,in-progress,detached
+        #[arg(short, long)]
         #[arg(default_value_t = false)]
         status: bool,
-        /// Print only the differences between local repositories and their remotes
-        #[arg(short, long, group = "output")]
+        /// Print only the differences between local repositories and their remotes, same as
+        /// --only unpushed,behind,no-remote,missing-upstream,fetch-failed
+        #[arg(short, long)]
+        #[arg(default_value_t = false)]
+        remotes: bool,
+        /// Report only the repositories and findings meeting any of the given
+        /// conditions, can be comma-separated or used multiple times
+        #[arg(long, value_name = "CONDITION", value_delimiter = ',')]
+        only: Vec<Condition>,
+        /// Order of the repositories [default: the order they are tracked or given in]
+        #[arg(long, value_name = "KEY")]
+        sort: Option<SortKey>,
+        /// How to show the progress, nothing is shown if the standard error isn't a terminal
+        #[arg(long, value_name = "STYLE", default_value = "spinner")]
+        progress: Progress,
+        /// Maximum number of repositories inspected at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>,
+        /// Maximum number of remotes fetched at once
+        #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_FETCH_JOBS).unwrap())]
+        fetch_jobs: NonZeroUsize,
+        /// Compare against the last fetched state of the remotes without fetching
+        #[arg(long, group = "fetching")]
+        #[arg(default_value_t = false)]
+        no_fetch: bool,
+        /// Fetch only if the last fetch is older than the given duration (e.g. 30m, 12h, 7d)
+        #[arg(long, group = "fetching", value_name = "DURATION", value_parser = parse_duration)]
+        fetch_only_stale: Option<Duration>,
+        /// Time limit of a single fetch, prefix with a remote name to apply it only
+        /// to that remote (e.g. 30s, upstream=1m), can be used multiple times, overrides
+        /// conform.fetchTimeout and remote.<name>.conformFetchTimeout of git config [default: 10s]
+        #[arg(long, value_name = "[REMOTE=]DURATION", value_parser = parse_fetch_timeout)]
+        fetch_timeout: Vec<(Option<String>, Duration)>,
+        /// Compare every branch with the same-named branch on every remote
+        /// instead of its configured upstream
+        #[arg(long)]
+        #[arg(default_value_t = false)]
+        all_remotes: bool,
+        /// List local branches merged into the default branch, with a gone upstream
+        /// or without commits for longer than the given duration [default: 90d]
+        #[arg(long, value_name = "MAX_AGE", num_args = 0..=1, require_equals = true, default_missing_value = "90d", value_parser = parse_duration)]
+        branches: Option<Duration>,
+        /// List every changed file instead of a summary
+        #[arg(short, long)]
+        #[arg(default_value_t = false)]
+        verbose: bool,
+        /// Print one row per repository
+        #[arg(long, group = "format")]
+        #[arg(default_value_t = false)]
+        table: bool,
+        /// Print the results as JSON
+        #[arg(long, group = "format")]
+        #[arg(default_value_t = false)]
+        json: bool,
+        /// Exit with a status telling what has been found: 8 if a repository is dirty,
+        /// 16 if a branch has unpushed commits, 32 if a branch is behind its remote,
+        /// 64 if errors were encountered, added up if several apply
+        #[arg(long)]
+        #[arg(default_value_t = false)]
+        exit_code: bool
+    },
+    /// Fast-forward the current branch of specified repositories to its upstream,
+    /// skipping repositories with uncommitted changes, diverged branches or
+    /// unfinished operations
+    Sync {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Sync all tracked repositories
+        #[arg(short, long, group = "repositories")]
         #[arg(default_value_t = false)]
-        remotes: bool
+        all: bool,
+        /// Only print what would be done
+        #[arg(short = 'n', long)]
+        #[arg(default_value_t = false)]
+        dry_run: bool,
+        /// Maximum number of repositories inspected and updated at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>,
+        /// Maximum number of remotes fetched at once
+        #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_FETCH_JOBS).unwrap())]
+        fetch_jobs: NonZeroUsize,
+        /// Compare against the last fetched state of the remotes without fetching
+        #[arg(long)]
+        #[arg(default_value_t = false)]
+        no_fetch: bool,
+        /// How to show the progress, nothing is shown if the standard error isn't a terminal
+        #[arg(long, value_name = "STYLE", default_value = "spinner")]
+        progress: Progress
+    },
+    /// Fetch all remotes of specified repositories concurrently
+    Fetch {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Fetch all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Maximum number of repositories processed at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>,
+        /// Maximum number of remotes fetched at once
+        #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_FETCH_JOBS).unwrap())]
+        fetch_jobs: NonZeroUsize,
+        /// Time limit of a single fetch, prefix with a remote name to apply it only
+        /// to that remote (e.g. 30s, upstream=1m), can be used multiple times, overrides
+        /// conform.fetchTimeout and remote.<name>.conformFetchTimeout of git config [default: 10s]
+        #[arg(long, value_name = "[REMOTE=]DURATION", value_parser = parse_fetch_timeout)]
+        fetch_timeout: Vec<(Option<String>, Duration)>,
+        /// How to show the progress, nothing is shown if the standard error isn't a terminal
+        #[arg(long, value_name = "STYLE", default_value = "spinner")]
+        progress: Progress
+    },
+    /// Run a command in each of specified repositories,
+    /// e.g. `git conform exec --all -- git gc`
+    Exec {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Run the command in all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Maximum number of repositories the command runs in at once
+        #[arg(short, long, default_value_t = NonZeroUsize::MIN)]
+        jobs: NonZeroUsize,
+        /// Prefix every line of the output with the repository path
+        /// instead of printing the path above the output
+        #[arg(long)]
+        #[arg(default_value_t = false)]
+        prefix: bool,
+        /// Command to run along with its arguments, use `sh -c '...'` for shell syntax
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>
+    },
+    /// Search the tracked files of specified repositories with `git grep`
+    Grep {
+        /// Pattern to search for, a basic regular expression as in `git grep`
+        pattern: String,
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Search all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Maximum number of repositories searched at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>,
+        /// Print only the names of the files with matching lines
+        #[arg(short = 'l', long, group = "format")]
+        #[arg(default_value_t = false)]
+        files_with_matches: bool,
+        /// Print the number of matching lines per file
+        #[arg(short, long, group = "format")]
+        #[arg(default_value_t = false)]
+        count: bool,
+        /// Print every matching line as JSON
+        #[arg(long, group = "format")]
+        #[arg(default_value_t = false)]
+        json: bool
+    },
+    /// Print the recent commits of specified repositories as one chronological stream
+    Log {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Print the commits of all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Print only the commits newer than this, any date `git log --since`
+        /// accepts (e.g. 1.week, yesterday, 2024-06-01)
+        #[arg(long, value_name = "DATE", default_value = "1.week")]
+        since: String,
+        /// Print only the commits by authors matching this pattern
+        #[arg(long, value_name = "PATTERN")]
+        author: Option<String>,
+        /// Maximum number of repositories read at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>
+    },
+    /// Write a manifest of all tracked repositories with their remotes,
+    /// current branches and tags, to reproduce them on another machine
+    Export {
+        /// Manifest format [default: guessed from the output file extension, toml otherwise]
+        #[arg(short, long)]
+        format: Option<ManifestFormat>,
+        /// File to write the manifest to instead of the standard output
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>
+    },
+    /// Clone the repositories of a manifest written by `export` that are missing,
+    /// reconcile the remotes of the existing ones and track all of them
+    Import {
+        /// Manifest file, or the repository list of the tool given with --from (`-` for the standard input)
+        file: String,
+        /// Manifest format [default: guessed from the file extension, toml otherwise]
+        #[arg(short, long, conflicts_with = "from")]
+        format: Option<ManifestFormat>,
+        /// Read the repository list of another tool: a myrepos configuration, a gita
+        /// repository list, the output of `ghq list` or a `repo` manifest XML
+        #[arg(long, value_name = "TOOL")]
+        from: Option<ImportSource>,
+        /// Maximum number of repositories cloned at once
+        #[arg(short, long, default_value_t = NonZeroUsize::new(DEFAULT_FETCH_JOBS).unwrap())]
+        jobs: NonZeroUsize,
+        /// Number of times a failed clone is retried
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        retries: u32
+    },
+    /// Push the branches of specified repositories that are ahead of their
+    /// remote, asking for confirmation per repository, never forcibly
+    Push {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Push branches of all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Push without asking for confirmation
+        #[arg(short, long)]
+        #[arg(default_value_t = false)]
+        yes: bool,
+        /// Maximum number of repositories inspected at once [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<NonZeroUsize>,
+        /// Maximum number of remotes fetched at once
+        #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_FETCH_JOBS).unwrap())]
+        fetch_jobs: NonZeroUsize,
+        /// Compare against the last fetched state of the remotes without fetching
+        #[arg(long)]
+        #[arg(default_value_t = false)]
+        no_fetch: bool,
+        /// How to show the progress, nothing is shown if the standard error isn't a terminal
+        #[arg(long, value_name = "STYLE", default_value = "spinner")]
+        progress: Progress
+    },
+    /// Manage local branches of specified repositories
+    Branches {
+        #[command(subcommand)]
+        command: BranchesCommands
+    }
+}
+
+/// Subcommands of the `branches` command
+#[derive(Subcommand)]
+pub enum BranchesCommands {
+    /// Delete local branches merged into the default branch, with a
+    /// gone upstream or without recent commits, after confirmation,
+    /// unmerged branches are kept unless forced
+    Prune {
+        #[arg(required = true, group = "repositories")]
+        repos: Vec<String>,
+        /// Prune branches of all tracked repositories
+        #[arg(short, long, group = "repositories")]
+        #[arg(default_value_t = false)]
+        all: bool,
+        /// Delete branches without commits for longer than this [default: 90d]
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        max_age: Option<Duration>,
+        /// Delete unmerged branches with a gone upstream or without recent commits as well
+        #[arg(short, long)]
+        #[arg(default_value_t = false)]
+        force: bool,
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        #[arg(default_value_t = false)]
+        yes: bool
+    }
+}
+
+// Parses a fetch timeout optionally preceded by a remote name
+fn parse_fetch_timeout(s: &str) -> Result<(Option<String>, Duration), String> {
+    match s.split_once('=') {
+        Some((remote, duration)) => Ok((Some(remote.to_string()), parse_duration(duration)?)),
+        None => Ok((None, parse_duration(s)?))
     }
 }
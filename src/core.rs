@@ -1,4 +1,7 @@
 //! Key functionality of the application
 
 pub mod api;
+pub mod git;
+pub mod report;
+pub mod manifest;
 mod backend;
@@ -5,8 +5,21 @@
 
 use crate::core::backend::{
     search_for_repos,
-    exec_async_check
+    exec_async_check,
+    exec_list_activity,
+    exec_sync,
+    exec_push,
+    exec_fetch,
+    exec_command,
+    exec_grep,
+    exec_log,
+    exec_export,
+    exec_import,
+    exec_prune_branches
 };
+use crate::core::git::{GitBackend, GitCli};
+use crate::core::report::{Condition, CheckSummary, SortKey, ImportOutcome};
+use crate::core::manifest::{Manifest, ManifestFormat, ImportSource};
 use crate::utils::{
     APP_NAME,
     TrackingFile,
@@ -15,13 +28,163 @@ use crate::utils::{
 };
 
 use std::fs::{self, OpenOptions};
-use std::io::Write as _;
-use std::path::Path;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::env;
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+use std::num::NonZeroUsize;
 
 use colored::Colorize;
+use clap::ValueEnum;
+
+/// Default maximum number of fetches running at once
+pub const DEFAULT_FETCH_JOBS: usize = 4;
+
+/// Default time limit of a single fetch
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time without commits after which a branch is considered stale
+pub const DEFAULT_STALE_AGE: Duration = Duration::from_hours(24 * 90);
+
+/// Determines whether remotes get fetched before comparing branches
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FetchMode {
+    /// Always fetch all remotes
+    Always,
+    /// Never fetch, use the last-known remote-tracking refs
+    Never,
+    /// Fetch only if the last fetch is older than the given duration
+    IfStale(Duration)
+}
+
+/// Time limits of fetching, global and per remote name
+#[derive(Clone, Default, Debug)]
+pub struct FetchTimeouts {
+    /// Applies to remotes without a dedicated timeout
+    pub global: Option<Duration>,
+    pub remotes: HashMap<String, Duration>
+}
+
+impl FetchTimeouts {
+    /// Sets the timeout for a given remote, or the global one if there is no remote
+    pub fn set(&mut self, remote: Option<String>, timeout: Duration) {
+        match remote {
+            Some(remote) => {
+                self.remotes.insert(remote, timeout);
+            },
+            None => self.global = Some(timeout)
+        }
+    }
+
+    /// Returns the timeout for a given remote, the timeouts set here take precedence
+    /// over the configured ones, `DEFAULT_FETCH_TIMEOUT` applies if there are none
+    #[must_use]
+    pub fn get(&self, remote: &str, configured: &Self) -> Duration {
+        self.remotes.get(remote)
+            .or(self.global.as_ref())
+            .or_else(|| configured.remotes.get(remote))
+            .or(configured.global.as_ref())
+            .copied()
+            .unwrap_or(DEFAULT_FETCH_TIMEOUT)
+    }
+}
+
+/// Presentation of the `check` results
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    /// Details of each repository with pending changes
+    Text,
+    /// One row per repository
+    Table,
+    /// Everything collected, as JSON
+    Json
+}
+
+/// Presentation of the `grep` results
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrepFormat {
+    /// Every matching line
+    Lines,
+    /// Only the names of the files with matching lines
+    FilesWithMatches,
+    /// Number of matching lines per file
+    Count,
+    /// Every matching line, as JSON
+    Json
+}
+
+/// Progress indication while `check` is running, shown only on terminals
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Progress {
+    /// No progress indication
+    None,
+    /// One spinner per repository being inspected
+    Spinner,
+    /// A single bar counting the inspected repositories
+    Bar
+}
+
+/// Order of the repositories listed by `list`
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListSort {
+    /// Alphabetically by path
+    Path,
+    /// Most recently active first
+    Activity
+}
+
+/// Settings of the `check` command
+#[derive(Clone)]
+pub struct CheckOptions {
+    /// Maximum number of repositories inspected at once
+    pub jobs: usize,
+    /// Maximum number of remotes fetched at once
+    pub fetch_jobs: usize,
+    /// When to fetch the remotes
+    pub fetch: FetchMode,
+    /// How long to wait for a fetch to finish
+    pub fetch_timeouts: FetchTimeouts,
+    /// Compare every branch with the same-named branch on every remote
+    pub all_remotes: bool,
+    /// List every changed file instead of a summary
+    pub verbose: bool,
+    /// How to print the results
+    pub format: OutputFormat,
+    /// Report only the repositories and findings meeting
+    /// any of these conditions, all of them if empty
+    pub only: Vec<Condition>,
+    /// Order of the repositories, `None` keeps the order they have been given in
+    pub sort: Option<SortKey>,
+    /// How to show the progress
+    pub progress: Progress,
+    /// Look for stale local branches, the ones without commits
+    /// for longer than this included, `None` skips the analysis
+    pub stale_branches: Option<Duration>
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            jobs: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            fetch_jobs: DEFAULT_FETCH_JOBS,
+            fetch: FetchMode::Always,
+            fetch_timeouts: FetchTimeouts::default(),
+            all_remotes: false,
+            verbose: false,
+            format: OutputFormat::Text,
+            only: Vec::new(),
+            sort: None,
+            progress: Progress::Spinner,
+            stale_branches: None
+        }
+    }
+}
 
 /// Scans only specified directories
-pub fn scan_dirs(mut dirs: Vec<String>, tracking_file: &TrackingFile, scan_hidden: bool) -> Result<String, String> {
+pub async fn scan_dirs(mut dirs: Vec<String>, tracking_file: &TrackingFile, scan_hidden: bool) -> Result<String, String> {
     // Remove duplicates
     dirs.sort_unstable();
     dirs.dedup();
@@ -70,12 +233,12 @@ pub fn scan_dirs(mut dirs: Vec<String>, tracking_file: &TrackingFile, scan_hidde
         return Err(String::from("Directories validation failed"));
     }
 
-    Ok(search_for_repos(dirs.as_slice(), tracking_file, scan_hidden)?)
+    search_for_repos(dirs.as_slice(), tracking_file, scan_hidden, &GitCli).await
 }
 
 /// Scans all directories in user's /home
-pub fn scan_all(home_dir: String, tracking_file: &TrackingFile, scan_hidden: bool) -> Result<String, String> {
-    Ok(search_for_repos(&[home_dir], tracking_file, scan_hidden)?)
+pub async fn scan_all(home_dir: String, tracking_file: &TrackingFile, scan_hidden: bool) -> Result<String, String> {
+    search_for_repos(&[home_dir], tracking_file, scan_hidden, &GitCli).await
 }
 
 /// Prints the paths of all tracked git repositories to the standard output
@@ -89,6 +252,23 @@ pub fn list(track_file_contents: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Prints the paths of all tracked git repositories along with the time
+/// elapsed since their last commit, local modification or fetch, only the
+/// ones inactive for longer than `stale` if given, in the given order,
+/// reading at most `jobs` repositories at once
+pub async fn list_activity(track_file_contents: &str, sort: Option<ListSort>, stale: Option<Duration>, jobs: usize) -> Result<(), String> {
+    if track_file_contents.is_empty() {
+        return Err(String::from("No repository is being tracked"));
+    }
+
+    let repos: Vec<String> = track_file_contents
+        .lines()
+        .map(String::from)
+        .collect();
+
+    exec_list_activity(repos, sort, stale, jobs, Arc::new(GitCli)).await
+}
+
 /// Writes the paths of the specified repos to the tracking file
 pub fn add(mut repos: Vec<String>, tracking_file: &TrackingFile) -> Result<(), String> {
     // Remove duplicates
@@ -186,34 +366,355 @@ pub fn remove_all(tracking_file: &TrackingFile) -> Result<(), String> {
 }
 
 /// Asynchronously retrieves important details about each repo
-/// in the repos Vec and prints them to the standard output
-pub async fn check_repos(mut repos: Vec<String>, flags: &[bool]) -> Result<(), String> {
+/// in the repos Vec and prints them to the standard output,
+/// returns a summary of the encountered problems
+pub async fn check_repos(mut repos: Vec<String>, options: &CheckOptions) -> Result<CheckSummary, String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_async_check(repos, options.clone(), Arc::new(GitCli)).await
+}
+
+/// Asynchronously retrieves important details about each repo
+/// in the tracking file and prints them to the standard output,
+/// returns a summary of the encountered problems
+pub async fn check_all(tracking_file: &TrackingFile, options: &CheckOptions) -> Result<CheckSummary, String> {
+    check_all_with(tracking_file, options, Arc::new(GitCli)).await
+}
+
+/// Same as `check_all`, but performs all git operations through the given backend
+pub async fn check_all_with(tracking_file: &TrackingFile, options: &CheckOptions, backend: Arc<dyn GitBackend>) -> Result<CheckSummary, String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_async_check(repos, options.clone(), backend).await
+}
+
+/// Deletes the local branches of the given repositories that are merged into
+/// the default branch, have a gone upstream or no commits for longer than
+/// `max_age`, the unmerged ones only if `force` is set, asks for confirmation
+/// first unless `yes` is set
+pub async fn prune_branches(mut repos: Vec<String>, max_age: Duration, force: bool, yes: bool) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_prune_branches(repos.as_slice(), max_age, force, yes, &GitCli).await
+}
+
+/// Same as `prune_branches`, but for all tracked repositories
+pub async fn prune_all_branches(tracking_file: &TrackingFile, max_age: Duration, force: bool, yes: bool) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_prune_branches(repos.as_slice(), max_age, force, yes, &GitCli).await
+}
+
+/// Fast-forwards the current branch of each given repository to its upstream
+/// if the working tree is clean and the branch is strictly behind, reports the
+/// skipped repositories, only tells what would be done if `dry_run` is set
+pub async fn sync_repos(mut repos: Vec<String>, options: &CheckOptions, dry_run: bool) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_sync(repos, options.clone(), dry_run, Arc::new(GitCli)).await
+}
+
+/// Same as `sync_repos`, but for all tracked repositories
+pub async fn sync_all(tracking_file: &TrackingFile, options: &CheckOptions, dry_run: bool) -> Result<(), String> {
+    sync_all_with(tracking_file, options, dry_run, Arc::new(GitCli)).await
+}
+
+/// Same as `sync_all`, but performs all git operations through the given backend
+pub async fn sync_all_with(tracking_file: &TrackingFile, options: &CheckOptions, dry_run: bool, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_sync(repos, options.clone(), dry_run, backend).await
+}
+
+/// Pushes the branches of the given repositories that are ahead of their push
+/// destination, asking for confirmation per repository unless `yes` is set,
+/// never force-pushes and reports the diverged branches and rejected pushes
+pub async fn push_repos(mut repos: Vec<String>, options: &CheckOptions, yes: bool) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_push(repos, options.clone(), yes, Arc::new(GitCli)).await
+}
+
+/// Same as `push_repos`, but for all tracked repositories
+pub async fn push_all(tracking_file: &TrackingFile, options: &CheckOptions, yes: bool) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_push(repos, options.clone(), yes, Arc::new(GitCli)).await
+}
+
+/// Fetches all remotes of the given repositories concurrently, within the limits
+/// of `options.fetch_jobs` and `options.fetch_timeouts`, reports the failures
+pub async fn fetch_repos(mut repos: Vec<String>, options: &CheckOptions) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_fetch(repos, options.clone(), Arc::new(GitCli)).await
+}
+
+/// Same as `fetch_repos`, but for all tracked repositories
+pub async fn fetch_all(tracking_file: &TrackingFile, options: &CheckOptions) -> Result<(), String> {
+    fetch_all_with(tracking_file, options, Arc::new(GitCli)).await
+}
+
+/// Same as `fetch_all`, but performs all git operations through the given backend
+pub async fn fetch_all_with(tracking_file: &TrackingFile, options: &CheckOptions, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_fetch(repos, options.clone(), backend).await
+}
+
+/// Runs a command with its arguments in each given repository, at most `jobs`
+/// at once, prints the output of each repository in one piece or with every
+/// line prefixed by the repository path, then lists the failed runs
+pub async fn exec_repos(mut repos: Vec<String>, command: Vec<String>, jobs: usize, prefix: bool) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_command(repos, command, jobs, prefix).await
+}
+
+/// Same as `exec_repos`, but for all tracked repositories
+pub async fn exec_all(tracking_file: &TrackingFile, command: Vec<String>, jobs: usize, prefix: bool) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_command(repos, command, jobs, prefix).await
+}
+
+/// Searches the tracked files of each given repository for a pattern with
+/// `git grep`, at most `jobs` repositories at once, prints the results
+/// prefixed with the repository path in the given format
+pub async fn grep_repos(mut repos: Vec<String>, pattern: &str, jobs: usize, format: GrepFormat) -> Result<(), String> {
+    // Remove duplicates
+    repos.sort_unstable();
+    repos.dedup();
+
+    repos = repos_valid(repos.as_slice())?;
+
+    exec_grep(repos, pattern, jobs, format, Arc::new(GitCli)).await
+}
+
+/// Same as `grep_repos`, but for all tracked repositories
+pub async fn grep_all(tracking_file: &TrackingFile, pattern: &str, jobs: usize, format: GrepFormat) -> Result<(), String> {
+    grep_all_with(tracking_file, pattern, jobs, format, Arc::new(GitCli)).await
+}
+
+/// Same as `grep_all`, but performs all git operations through the given backend
+pub async fn grep_all_with(tracking_file: &TrackingFile, pattern: &str, jobs: usize, format: GrepFormat, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_grep(repos, pattern, jobs, format, backend).await
+}
+
+/// Prints the commits of the given repositories newer than `since`, only the
+/// ones by a matching author if given, merged into one chronological stream
+pub async fn log_repos(mut repos: Vec<String>, since: &str, author: Option<&str>, jobs: usize) -> Result<(), String> {
     // Remove duplicates
     repos.sort_unstable();
     repos.dedup();
 
     repos = repos_valid(repos.as_slice())?;
 
-    exec_async_check(repos, flags.to_vec()).await?;
+    exec_log(repos, since, author, jobs, Arc::new(GitCli)).await
+}
+
+/// Same as `log_repos`, but for all tracked repositories
+pub async fn log_all(tracking_file: &TrackingFile, since: &str, author: Option<&str>, jobs: usize) -> Result<(), String> {
+    log_all_with(tracking_file, since, author, jobs, Arc::new(GitCli)).await
+}
+
+/// Same as `log_all`, but performs all git operations through the given backend
+pub async fn log_all_with(tracking_file: &TrackingFile, since: &str, author: Option<&str>, jobs: usize, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    exec_log(repos, since, author, jobs, backend).await
+}
+
+/// Writes a manifest of all tracked repositories with their paths relative to
+/// the home directory, remotes, current branches and tags to the output file
+/// or the standard output, in the given format or the one the file extension
+/// suggests, TOML by default
+pub async fn export(tracking_file: &TrackingFile, home_dir: &str, format: Option<ManifestFormat>, output: Option<&str>) -> Result<(), String> {
+    export_with(tracking_file, home_dir, format, output, &GitCli).await
+}
+
+/// Same as `export`, but performs all git operations through the given backend
+pub async fn export_with(tracking_file: &TrackingFile, home_dir: &str, format: Option<ManifestFormat>, output: Option<&str>, backend: &dyn GitBackend) -> Result<(), String> {
+    let repos = tracked_repos(tracking_file)?;
+
+    let format = format
+        .or_else(|| output.and_then(ManifestFormat::from_path))
+        .unwrap_or(ManifestFormat::Toml);
+
+    let manifest = exec_export(repos.as_slice(), home_dir, backend).await?.to_string(format)?;
+
+    if let Some(path) = output {
+        return fs::write(path, manifest).map_err(|e| format!("{path}: {e}"));
+    }
+
+    print!("{manifest}");
 
     Ok(())
 }
 
-/// Asynchronously retrieves important details about each repo
-/// in the tracking file and prints them to the standard output
-pub async fn check_all(tracking_file: &TrackingFile, flags: &[bool]) -> Result<(), String> {
+/// Reads a manifest written by `export`, in the given format or the one the
+/// file extension suggests, TOML by default, clones the missing repositories
+/// at most `jobs` at once, retrying failed clones, reconciles the remotes of
+/// the existing ones and adds all of them to the tracking file
+pub async fn import(manifest_path: &str, format: Option<ManifestFormat>, tracking_file: &TrackingFile, home_dir: &str, jobs: usize, retries: u32) -> Result<(), String> {
+    import_with(manifest_path, format, tracking_file, home_dir, jobs, retries, Arc::new(GitCli)).await
+}
+
+/// Same as `import`, but performs all git operations through the given backend
+pub async fn import_with(manifest_path: &str, format: Option<ManifestFormat>, tracking_file: &TrackingFile, home_dir: &str, jobs: usize, retries: u32, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let contents = fs::read_to_string(manifest_path).map_err(|e| format!("{manifest_path}: {e}"))?;
+
+    let format = format
+        .or_else(|| ManifestFormat::from_path(manifest_path))
+        .unwrap_or(ManifestFormat::Toml);
+
+    let manifest = Manifest::parse(contents.as_str(), format).map_err(|e| format!("{manifest_path}: {e}"))?;
+
+    import_manifest(manifest, tracking_file, home_dir, jobs, retries, backend).await
+}
+
+/// Same as `import`, but reads the repository list of another tool, `-` reads
+/// it from the standard input, e.g. the output of `ghq list`
+pub async fn import_from(source: ImportSource, path: &str, tracking_file: &TrackingFile, home_dir: &str, jobs: usize, retries: u32) -> Result<(), String> {
+    import_from_with(source, path, tracking_file, home_dir, jobs, retries, Arc::new(GitCli)).await
+}
+
+/// Same as `import_from`, but performs all git operations through the given backend
+pub async fn import_from_with(source: ImportSource, path: &str, tracking_file: &TrackingFile, home_dir: &str, jobs: usize, retries: u32, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let contents = if path == "-" {
+        io::read_to_string(io::stdin()).map_err(|e| format!("stdin: {e}"))?
+    }
+    else {
+        fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?
+    };
+
+    // Relative paths of a list read from the standard input start at the current directory
+    let file = if path == "-" {
+        None
+    }
+    else {
+        Some(Path::new(path).canonicalize().map_err(|e| format!("{path}: {e}"))?)
+    };
+    let file_dir = match file.as_deref().and_then(Path::parent) {
+        Some(dir) => dir.to_path_buf(),
+        None => env::current_dir().map_err(|e| format!("Could not obtain the current directory: {e}"))?
+    };
+
+    // Find the directory the relative paths start at
+    let base = match source {
+        ImportSource::Mr | ImportSource::Gita => file_dir,
+        ImportSource::Ghq => env::var("GHQ_ROOT")
+            .ok()
+            .and_then(|roots| roots.split(':').next().map(PathBuf::from))
+            .filter(|root| !root.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new(home_dir).join("ghq")),
+        ImportSource::RepoManifest => file
+            .as_deref()
+            .and_then(|file| file.ancestors().find(|dir| dir.file_name().is_some_and(|name| name == ".repo")))
+            .and_then(Path::parent)
+            .map_or(file_dir, Path::to_path_buf)
+    };
+
+    // Relative fetch URLs of `repo` start at the URL of its manifest repository
+    let manifests_dir = base.join(".repo/manifests");
+    let manifest_url = if source == ImportSource::RepoManifest && manifests_dir.is_dir() {
+        backend.remote_url(manifests_dir.to_string_lossy().as_ref(), "origin").await?
+    }
+    else {
+        None
+    };
+
+    let mut manifest = Manifest::from_source(contents.as_str(), source, base.as_path(), manifest_url.as_deref())
+        .map_err(|e| format!("{path}: {e}"))?;
+
+    // Gita doesn't record where the repositories come from,
+    // the missing ones are left to the user
+    if source == ImportSource::Gita {
+        manifest.repos.retain(|repo| {
+            let exists = Path::new(repo.path.as_str()).exists();
+            if !exists {
+                let outcome = ImportOutcome::Skipped(String::from("tracked only by gita, cannot clone"));
+                println!("{}: {outcome}", repo.path.bold());
+            }
+            exists
+        });
+    }
+
+    import_manifest(manifest, tracking_file, home_dir, jobs, retries, backend).await
+}
+
+// Clones or reconciles the repositories of a manifest
+// and tracks the ones that are present afterwards
+async fn import_manifest(manifest: Manifest, tracking_file: &TrackingFile, home_dir: &str, jobs: usize, retries: u32, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let outcomes = exec_import(manifest, home_dir, jobs, retries, backend).await?;
+
+    // Open/create the tracking file for writing
+    let mut track_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(tracking_file.path.clone())
+        .map_err(|e| format!("{}: {e}", tracking_file.path))?;
+
+    let mut failed = 0;
+    for (repo, outcome) in outcomes {
+        match outcome {
+            ImportOutcome::Failed(_) => {
+                failed += 1;
+                continue;
+            },
+            ImportOutcome::Skipped(_) => continue,
+            ImportOutcome::Cloned | ImportOutcome::Existing(_) => ()
+        }
+
+        if !repo_is_tracked(repo.as_str(), tracking_file.contents.as_str()) {
+            track_file.write_all(
+                format!("{repo}\n").as_bytes())
+                .map_err(|e| format!("{}: {e}", tracking_file.path))?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("Could not import {failed} repositories"));
+    }
+
+    Ok(())
+}
+
+// Puts all the tracking file entries in a Vec
+// to avoid lifetime constraints on async tasks
+fn tracked_repos(tracking_file: &TrackingFile) -> Result<Vec<String>, String> {
     if tracking_file.contents.is_empty() {
         return Err(String::from("No repository is being tracked"));
     }
 
-    // Put all the tracking file entries in a Vec to
-    // avoid lifetime constraints on async tasks
-    let track_file_lines: Vec<String> = tracking_file.contents
+    Ok(tracking_file.contents
         .lines()
         .map(String::from)
-        .collect();
-
-    exec_async_check(track_file_lines, flags.to_vec()).await?;
-
-    Ok(())
+        .collect())
 }
@@ -1,28 +1,51 @@
 //! Backend components of the core module
 
+use crate::core::api::{CheckOptions, FetchMode, FetchTimeouts, OutputFormat, GrepFormat, Progress, ListSort};
+use crate::core::git::{GitBackend, FetchOutcome, PushOutcome, Branch, RemoteBranch, Head};
+use crate::core::manifest::{Manifest, ManifestRepo, ManifestRemote};
+use crate::core::report::{
+    self,
+    RepoReport,
+    StatusReport,
+    BranchReport,
+    RemoteReport,
+    Divergence,
+    AheadBehind,
+    Condition,
+    CheckSummary,
+    StaleBranch,
+    StaleReason,
+    SyncOutcome,
+    ImportOutcome,
+    GrepReport,
+    SortKey
+};
 use crate::utils::{
     APP_NAME,
     SPINNER_TICK,
+    confirm,
+    stderr_text,
     TrackingFile,
-    repo_is_tracked,
-    path_is_repo
+    repo_is_tracked
 };
 
 use std::fs::{OpenOptions, File};
 use std::io::Write as _;
-use std::fmt::Write as _;
-use std::time::Duration;
-use std::process::{Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use std::sync::Arc;
+use std::process::Stdio;
+use std::path::Path;
+use std::cmp::Reverse;
 
+use tokio::sync::Semaphore;
 use walkdir::{WalkDir, DirEntry};
-use wait_timeout::ChildExt;
-use indicatif::{MultiProgress, ProgressBar};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use console::Term;
 use colored::Colorize;
 
 // Searches recursively in dirs for untracked git repositories and automatically adds them to the tracking file
 #[allow(clippy::redundant_closure_for_method_calls)]
-pub fn search_for_repos(dirs: &[String], tracking_file: &TrackingFile, scan_hidden: bool) -> Result<String, String> {
+pub async fn search_for_repos(dirs: &[String], tracking_file: &TrackingFile, scan_hidden: bool, backend: &dyn GitBackend) -> Result<String, String> {
     let mut repos = String::new();
 
     // Open/create the tracking file for writing
@@ -39,7 +62,7 @@ pub fn search_for_repos(dirs: &[String], tracking_file: &TrackingFile, scan_hidd
                 .same_file_system(true)
                 .into_iter()
                 .filter_map(|n| n.ok()) {
-                    match search_core(&entry, &track_file, tracking_file.path.as_str(), tracking_file.contents.as_str()) {
+                    match search_core(&entry, &track_file, tracking_file, backend).await {
                         Ok(s) => repos.push_str(&s),
                         Err(e) => return Err(e)
                     }
@@ -54,7 +77,7 @@ pub fn search_for_repos(dirs: &[String], tracking_file: &TrackingFile, scan_hidd
                 .into_iter()
                 .filter_entry(|n| !entry_is_hidden(n))
                 .filter_map(|n| n.ok()) {
-                    match search_core(&entry, &track_file, tracking_file.path.as_str(), tracking_file.contents.as_str()) {
+                    match search_core(&entry, &track_file, tracking_file, backend).await {
                         Ok(s) => repos.push_str(&s),
                         Err(e) => return Err(e)
                     }
@@ -66,32 +89,26 @@ pub fn search_for_repos(dirs: &[String], tracking_file: &TrackingFile, scan_hidd
 }
 
 // Core functionality of the `search_for_repos` function
-fn search_core(entry: &DirEntry, mut track_file: &File, track_file_path: &str, track_file_contents: &str) -> Result<String, String> {
+async fn search_core(entry: &DirEntry, mut track_file: &File, tracking_file: &TrackingFile, backend: &dyn GitBackend) -> Result<String, String> {
     let mut repo = String::new();
 
     // Check if the path contains .git directory
-    if let Some(path) = entry.path().to_str() {
-        if let Some(repo_path) = path.strip_suffix("/.git") {
-            // Check if the tracking file already
-            // contains the git repository path
-            if repo_is_tracked(repo_path, track_file_contents) {
-                return Ok(repo)
-            }
+    if let Some(path) = entry.path().to_str()
+        && let Some(repo_path) = path.strip_suffix("/.git") {
+        // Check if the tracking file already
+        // contains the git repository path
+        if repo_is_tracked(repo_path, tracking_file.contents.as_str()) {
+            return Ok(repo)
+        }
 
-            // Check if the path is in fact a git repository
-            match path_is_repo(repo_path) {
-                Ok(is_repo) => {
-                    if is_repo {
-                        // Add the path of the git repository to the tracking file
-                        track_file.write_all(
-                            format!("{repo_path}\n").as_bytes())
-                            .map_err(|e| format!("{track_file_path}: {e}"))?;
-                        
-                        repo = format!("{repo_path}\n");
-                    }
-                },
-                Err(e) => return Err(e)
-            };
+        // Check if the path is in fact a git repository
+        if backend.is_repo(repo_path).await? {
+            // Add the path of the git repository to the tracking file
+            track_file.write_all(
+                format!("{repo_path}\n").as_bytes())
+                .map_err(|e| format!("{}: {e}", tracking_file.path))?;
+
+            repo = format!("{repo_path}\n");
         }
     }
 
@@ -107,239 +124,1062 @@ fn entry_is_hidden(entry: &DirEntry) -> bool {
 }
 
 // Core functionality of the `check` command
-pub async fn exec_async_check(repos: Vec<String>, flags: Vec<bool>) -> Result<(), String> {
-    let final_output = Arc::new(Mutex::new(String::new()));
+pub async fn exec_async_check(repos: Vec<String>, options: CheckOptions, backend: Arc<dyn GitBackend>) -> Result<CheckSummary, String> {
+    let mut reports = collect_reports(repos, &options, false, backend).await?;
 
-    // Handler for async spinners
-    let multi_prog = MultiProgress::new();
+    if let Some(key) = options.sort {
+        report::sort(&mut reports, key);
+    }
 
-    // Create an async task for each repo
+    // Keep only the repositories meeting the selected conditions,
+    // and the ones that couldn't be inspected
+    if !options.only.is_empty() {
+        reports.retain(|r| r.error.is_some() || r.has_findings(&options.only));
+    }
+
+    match options.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())?),
+        OutputFormat::Table => {
+            // Fit the table in the terminal, if there is one
+            let term = Term::stdout();
+            let max_width = term.is_term().then(|| usize::from(term.size().1));
+
+            let (failed, inspected): (Vec<RepoReport>, Vec<RepoReport>) = reports
+                .iter()
+                .cloned()
+                .partition(|r| r.error.is_some());
+
+            print!("{}", report::table(&inspected, max_width));
+            eprint!("{}", stderr_text(report::errors(&failed).as_str()));
+        },
+        OutputFormat::Text => {
+            // Print only the repositories with pending changes
+            for report in reports.iter().filter(|r| r.has_findings(&options.only)) {
+                if options.verbose {
+                    println!("{:#}", report.findings(&options.only));
+                }
+                else {
+                    println!("{}", report.findings(&options.only));
+                }
+            }
+
+            eprint!("{}", stderr_text(report::errors(&reports).as_str()));
+        }
+    }
+
+    // Gather the selected conditions met by any of the repositories
+    let mut conditions: Vec<Condition> = reports.iter()
+        .flat_map(RepoReport::conditions)
+        .filter(|c| c.is_selected(&options.only))
+        .collect();
+    conditions.sort_unstable();
+    conditions.dedup();
+
+    Ok(CheckSummary {
+        conditions,
+        errors: reports.iter().filter(|r| r.error.is_some()).count()
+    })
+}
+
+// Core functionality of the `sync` command
+pub async fn exec_sync(repos: Vec<String>, options: CheckOptions, dry_run: bool, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let reports = collect_reports(repos, &options, false, backend.clone()).await?;
+
+    let jobs = Arc::new(Semaphore::new(options.jobs.max(1)));
+
+    // Fast-forward the repositories that are safe to update concurrently
     let mut tasks = Vec::new();
-    for repo in repos {
-        let multi_prog_clone = multi_prog.clone();
-        let final_output_clone = Arc::clone(&final_output);
-        let flags_clone = flags.clone();
+    for report in reports {
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
 
         tasks.push(tokio::spawn(async move {
-            let spinner = multi_prog_clone.add(ProgressBar::new_spinner());
-            spinner.set_message(repo.bold().to_string());
-            spinner.enable_steady_tick(Duration::from_millis(SPINNER_TICK));
-
-            match inspect_repo(repo.as_str(), flags_clone.as_slice()) {
-                Ok(output) => {
-                    spinner.finish_and_clear();
-
-                    if !output.is_empty() {
-                        writeln!(
-                            final_output_clone
-                                .lock()
-                                .unwrap_or_else(|_| panic!("'{repo}' Mutex lock")),
-                            "{output}"
-                        )
-                        .unwrap_or_else(|_| panic!("'{repo}' output write"));
+            let outcome = match report.fast_forward_target() {
+                Ok((branch, _, commits)) if dry_run => SyncOutcome::WouldFastForward { branch: branch.to_string(), commits },
+                Ok((branch, upstream, commits)) => {
+                    let _permit = jobs_clone
+                        .acquire()
+                        .await
+                        .unwrap_or_else(|_| panic!("'{}' semaphore acquire", report.path));
+
+                    match backend_clone.fast_forward(report.path.as_str(), upstream).await {
+                        Ok(()) => SyncOutcome::FastForwarded { branch: branch.to_string(), commits },
+                        Err(e) => SyncOutcome::Failed(e)
                     }
                 },
-                Err(e) => spinner.finish_with_message(format!("{APP_NAME}: {e}"))
+                Err(outcome) => outcome
             };
+
+            (report.path, outcome)
         }));
     }
 
-    // Execute the tasks
+    let mut failed = 0;
+    let mut updated = 0;
     for task in tasks {
-        task.await.map_err(|e| e.to_string())?;
+        let (path, outcome) = task.await.map_err(|e| e.to_string())?;
+
+        match outcome {
+            SyncOutcome::UpToDate => continue,
+            SyncOutcome::Failed(_) => failed += 1,
+            SyncOutcome::FastForwarded { .. } | SyncOutcome::WouldFastForward { .. } => updated += 1,
+            SyncOutcome::Skipped(_) => ()
+        }
+
+        println!("{}: {outcome}", path.bold());
     }
 
-    print!("{}", final_output.lock().unwrap());
+    if failed > 0 {
+        return Err(format!("Could not update {failed} repositories"));
+    }
+
+    if updated == 0 {
+        println!("{APP_NAME}: Nothing to fast-forward");
+    }
 
     Ok(())
 }
 
-// Retrieves the status of a given repository and the
-// difference in the number of commits between each branch
-// and the respective remote, returns a String with
-// the output of each operation
-fn inspect_repo(repo: &str, flags: &[bool]) -> Result<String, String> {
-    // Define the function flags
-    let print_status = flags[0];
-    let print_remotes = flags[1];
-
-    let mut status_output = String::new();
-    if !print_remotes {
-        status_output = repo_status(repo)?;
-    }
-
-    let mut final_output = String::new();
-
-    let mut remotes_output = String::new();
-    if !print_status {
-        // Get the list of branches
-        let git_branch_out = Command::new("git")
-            .args(["-C", repo, "branch"])
-            .stderr(Stdio::null())
-            .output()
-            .map_err(|e| format!("git: {e}"))?
-            .stdout;
-        let git_branch_str = String::from_utf8_lossy(git_branch_out.as_slice());
-
-        // Leave if there are no branches in the repository
-        if git_branch_str.is_empty() {
-            return Ok(final_output)
-        }
-
-        // Format each entry in the branches string and put it in Vec
-        let branches: Vec<String> = git_branch_str
-            .lines()
-            .map(|mut s| {
-                s = s.trim();
-                s.replace("* ", "")
-            })
-            .collect();
+// Core functionality of the `push` command
+pub async fn exec_push(repos: Vec<String>, options: CheckOptions, yes: bool, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    // Compare the branches with their remotes only
+    let options = CheckOptions {
+        only: vec![Condition::Unpushed],
+        ..options
+    };
 
-        // Get the list of remotes
-        let mut remotes: Vec<&str> = Vec::new();
-        let git_remote_out = Command::new("git")
-            .args(["-C", repo, "remote"])
-            .stderr(Stdio::null())
-            .output()
-            .map_err(|e| format!("git: {e}"))?
-            .stdout;
-        let git_remote_str = String::from_utf8_lossy(git_remote_out.as_slice());
-
-        // Populate the remotes Vec only if there are
-        // any remotes in the repository
-        if !git_remote_str.is_empty() {
-            remotes = git_remote_str.lines().collect();
-        }
-
-        // Fetch the latest data from remote repositories
-        for remote in &remotes {
-            let mut git_fetch = Command::new("git")
-                .args(["-C", repo, "fetch", remote])
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-                .map_err(|e| format!("git: {e}"))?;
-
-            // Wait 10 seconds for fetching to finish, if it's still
-            // running after that time, kill the process
-            if git_fetch.wait_timeout(Duration::from_secs(10))
-                .map_err(|e| format!("git fetch: {e}"))?
-                .is_none() {
-                git_fetch.kill().map_err(|e| format!("git fetch: {e}"))?;
-                git_fetch.wait().map_err(|e| format!("git fetch: {e}"))?;
-            }
+    let reports = collect_reports(repos, &options, false, backend.clone()).await?;
+
+    let mut found = false;
+    let mut failed = 0;
+
+    for report in &reports {
+        let unpushed = report.unpushed_branches();
+        if unpushed.is_empty() {
+            continue;
+        }
+        found = true;
+
+        println!("{}", report.path.bold());
+        for branch in &unpushed {
+            println!("  {branch}");
+        }
+
+        // Never force-push the diverged branches
+        let branches: Vec<_> = unpushed.iter().filter(|b| !b.has_diverged()).collect();
+        if branches.is_empty() {
+            continue;
+        }
+
+        if !yes && !confirm(format!("Push {} branch(es)?", branches.len()).as_str())? {
+            continue;
         }
 
-        // Inspect each branch
         for branch in branches {
-            write!(
-                remotes_output,
-                "{}",
-                remotes_diff(repo, branch.as_str(), remotes.clone())?
-            )
-            .map_err(|e| e.to_string())?;
+            let outcome = backend.push(report.path.as_str(), branch.name.as_str(), branch.remote.as_str(), branch.remote_ref())
+                .await
+                .unwrap_or_else(PushOutcome::Failed);
+
+            if outcome != PushOutcome::Pushed {
+                failed += 1;
+            }
+
+            println!("  {} -> {}: {outcome}", branch.name, branch.remote_branch);
+        }
+    }
+
+    eprint!("{}", stderr_text(report::errors(&reports).as_str()));
+
+    if failed > 0 {
+        return Err(format!("Could not push {failed} branch(es)"));
+    }
+
+    let errors = reports.iter().filter(|r| r.error.is_some()).count();
+    if errors > 0 {
+        return Err(format!("Could not inspect {errors} repositories"));
+    }
+
+    if !found {
+        println!("{APP_NAME}: Nothing to push");
+    }
+
+    Ok(())
+}
+
+// Core functionality of the `fetch` command
+pub async fn exec_fetch(repos: Vec<String>, options: CheckOptions, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    // Fetch every remote without inspecting anything else
+    let reports = collect_reports(repos, &options, true, backend).await?;
+
+    let mut fetched = 0;
+    let mut failed = 0;
+    for report in &reports {
+        fetched += report.remotes.iter().filter(|r| r.fetch == FetchOutcome::Fetched).count();
+
+        let failures: Vec<_> = report.remotes.iter().filter(|r| r.fetch_failed()).collect();
+        if failures.is_empty() {
+            continue;
+        }
+        failed += failures.len();
+
+        println!("{}", report.path.bold());
+        for remote in failures {
+            print!("{remote}");
+        }
+    }
+
+    eprint!("{}", stderr_text(report::errors(&reports).as_str()));
+
+    println!("{APP_NAME}: Fetched {fetched} remote(s) of {} repositories", reports.len());
+
+    if failed > 0 {
+        return Err(format!("Could not fetch {failed} remote(s)"));
+    }
+
+    let errors = reports.iter().filter(|r| r.error.is_some()).count();
+    if errors > 0 {
+        return Err(format!("Could not inspect {errors} repositories"));
+    }
+
+    Ok(())
+}
+
+// Core functionality of the `exec` command
+pub async fn exec_command(repos: Vec<String>, command: Vec<String>, jobs: usize, prefix: bool) -> Result<(), String> {
+    if command.is_empty() {
+        return Err(String::from("No command given"));
+    }
+
+    let total = repos.len();
+    let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+    let command = Arc::new(command);
+
+    // Run the command in each repository, at most `jobs` at once
+    let mut tasks = Vec::new();
+    for repo in repos {
+        let jobs_clone = Arc::clone(&jobs);
+        let command_clone = Arc::clone(&command);
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{repo}' semaphore acquire"));
+
+            let output = tokio::process::Command::new(&command_clone[0])
+                .args(&command_clone[1..])
+                .current_dir(&repo)
+                .stdin(Stdio::null())
+                .output()
+                .await
+                .map_err(|e| format!("{}: {e}", command_clone[0]));
+
+            (repo, output)
+        }));
+    }
+
+    // Print the output of each repository as a whole,
+    // in the order the repositories have been given in
+    let mut failures = Vec::new();
+    for task in tasks {
+        let (repo, output) = task.await.map_err(|e| e.to_string())?;
+
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => {
+                failures.push((repo, e));
+                continue;
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(output.stdout.as_slice());
+        let stderr = String::from_utf8_lossy(output.stderr.as_slice());
+
+        if prefix {
+            for line in stdout.lines() {
+                println!("{}: {line}", repo.bold());
+            }
+            for line in stderr.lines() {
+                eprintln!("{}", stderr_text(format!("{}: {line}", repo.bold()).as_str()));
+            }
+        }
+        else if !stdout.is_empty() || !stderr.is_empty() {
+            println!("{}", repo.bold());
+            print!("{stdout}");
+            eprint!("{stderr}");
+        }
+
+        if !output.status.success() {
+            let reason = match output.status.code() {
+                Some(code) => format!("exit code {code}"),
+                None => String::from("killed by a signal")
+            };
+            failures.push((repo, reason));
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{APP_NAME}: The command failed in the following repositories:");
+    for (repo, reason) in &failures {
+        eprintln!("{}", stderr_text(format!("  {}: {reason}", repo.bold()).as_str()));
+    }
+
+    Err(format!("The command failed in {} of {total} repositories", failures.len()))
+}
+
+// Core functionality of the `grep` command
+pub async fn exec_grep(repos: Vec<String>, pattern: &str, jobs: usize, format: GrepFormat, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+    let pattern = Arc::new(pattern.to_string());
+
+    // Search all repositories concurrently, at most `jobs` at once
+    let mut tasks = Vec::new();
+    for repo in repos {
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
+        let pattern_clone = Arc::clone(&pattern);
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{repo}' semaphore acquire"));
+
+            match backend_clone.grep(repo.as_str(), pattern_clone.as_str()).await {
+                Ok(matches) => GrepReport { path: repo, matches, error: None },
+                Err(e) => GrepReport { path: repo, matches: Vec::new(), error: Some(e) }
+            }
+        }));
+    }
+
+    // Collect the results in the order the repositories have been given in
+    let mut reports = Vec::new();
+    for task in tasks {
+        reports.push(task.await.map_err(|e| e.to_string())?);
+    }
+
+    let (failed, mut reports): (Vec<GrepReport>, Vec<GrepReport>) = reports.into_iter().partition(|r| r.error.is_some());
+    reports.retain(|r| !r.matches.is_empty());
+
+    match format {
+        GrepFormat::Json => println!("{}", serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())?),
+        GrepFormat::Lines => {
+            for report in &reports {
+                for m in &report.matches {
+                    println!("{}/{}:{}:{}", report.path.bold(), m.path, m.line, m.text);
+                }
+            }
+        },
+        GrepFormat::FilesWithMatches => {
+            for report in &reports {
+                for (path, _) in report.files() {
+                    println!("{}/{path}", report.path.bold());
+                }
+            }
+        },
+        GrepFormat::Count => {
+            for report in &reports {
+                for (path, count) in report.files() {
+                    println!("{}/{path}:{count}", report.path.bold());
+                }
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{APP_NAME}: Could not search the following repositories:");
+    for report in &failed {
+        let e = report.error.as_deref().unwrap_or_default();
+        eprintln!("{}", stderr_text(format!("  {}: {}", report.path.bold(), e.lines().next().unwrap_or_default()).as_str()));
+    }
+
+    Err(format!("Could not search {} repositories", failed.len()))
+}
+
+// Core functionality of the `log` command
+pub async fn exec_log(repos: Vec<String>, since: &str, author: Option<&str>, jobs: usize, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+    let since = Arc::new(since.to_string());
+    let author = Arc::new(author.map(String::from));
+
+    // Read the logs of all repositories concurrently, at most `jobs` at once
+    let mut tasks = Vec::new();
+    for repo in repos {
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
+        let since_clone = Arc::clone(&since);
+        let author_clone = Arc::clone(&author);
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{repo}' semaphore acquire"));
+
+            let entries = backend_clone.log(repo.as_str(), since_clone.as_str(), author_clone.as_deref()).await;
+
+            (repo, entries)
+        }));
+    }
+
+    let mut logs = Vec::new();
+    let mut failed = Vec::new();
+    for task in tasks {
+        match task.await.map_err(|e| e.to_string())? {
+            (repo, Ok(entries)) => logs.push((repo, entries)),
+            (repo, Err(e)) => failed.push((repo, e))
+        }
+    }
+
+    if logs.iter().all(|(_, entries)| entries.is_empty()) {
+        println!("{APP_NAME}: No commits found");
+    }
+    else {
+        print!("{}", report::log(&logs));
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{APP_NAME}: Could not read the log of the following repositories:");
+    for (repo, e) in &failed {
+        eprintln!("{}", stderr_text(format!("  {}: {e}", repo.bold()).as_str()));
+    }
+
+    Err(format!("Could not read the log of {} repositories", failed.len()))
+}
+
+// Core functionality of the `export` command, describes the repositories
+// with their paths relative to the home directory where possible
+pub async fn exec_export(repos: &[String], home_dir: &str, backend: &dyn GitBackend) -> Result<Manifest, String> {
+    let mut manifest = Manifest::default();
+
+    for repo in repos {
+        let branch = match backend.state(repo).await.map_err(|e| format!("{repo}: {e}"))?.head {
+            Head::Branch(branch) => Some(branch),
+            Head::Detached(_) => None
+        };
+
+        let mut remotes = Vec::new();
+        for name in backend.remotes(repo).await.map_err(|e| format!("{repo}: {e}"))? {
+            if let Some(url) = backend.remote_url(repo, name.as_str()).await.map_err(|e| format!("{repo}: {e}"))? {
+                remotes.push(ManifestRemote { name, url });
+            }
+        }
+
+        let path = Path::new(repo)
+            .strip_prefix(home_dir)
+            .ok()
+            .and_then(|p| p.to_str())
+            .filter(|p| !p.is_empty())
+            .unwrap_or(repo);
+
+        manifest.repos.push(ManifestRepo {
+            path: path.to_string(),
+            branch,
+            remotes,
+            tags: backend.head_tags(repo).await.map_err(|e| format!("{repo}: {e}"))?
+        });
+    }
+
+    Ok(manifest)
+}
+
+// Core functionality of the `import` command, clones the missing repositories
+// concurrently and brings the remotes of the existing ones in line with the
+// manifest, returns the absolute path and outcome for every repository
+pub async fn exec_import(manifest: Manifest, home_dir: &str, jobs: usize, retries: u32, backend: Arc<dyn GitBackend>) -> Result<Vec<(String, ImportOutcome)>, String> {
+    let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+
+    let mut tasks = Vec::new();
+    for repo in manifest.repos {
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
+
+        // Relative paths start at the home directory
+        let path = Path::new(home_dir).join(repo.path.as_str()).to_string_lossy().into_owned();
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{path}' semaphore acquire"));
+
+            let outcome = if Path::new(path.as_str()).exists() {
+                reconcile_repo(path.as_str(), &repo, backend_clone.as_ref()).await
+            }
+            else {
+                clone_repo(path.as_str(), &repo, retries, backend_clone.as_ref()).await
+            };
+
+            (path, outcome.unwrap_or_else(ImportOutcome::Failed))
+        }));
+    }
+
+    let mut outcomes = Vec::new();
+    for task in tasks {
+        let (path, outcome) = task.await.map_err(|e| e.to_string())?;
+        println!("{}: {outcome}", path.bold());
+        outcomes.push((path, outcome));
+    }
+
+    Ok(outcomes)
+}
+
+// Clones a manifest repository, retrying the given number of times, then
+// adds the rest of its remotes and checks out the recorded branch or tag
+async fn clone_repo(path: &str, repo: &ManifestRepo, retries: u32, backend: &dyn GitBackend) -> Result<ImportOutcome, String> {
+    let remote = repo.clone_remote().ok_or_else(|| String::from("no remote to clone from"))?;
+
+    let mut attempt = 0;
+    while let Err(e) = backend.clone(remote.url.as_str(), path, remote.name.as_str()).await {
+        if attempt == retries {
+            return Err(e);
+        }
+        attempt += 1;
+
+        // Give transient network problems some time to go away
+        tokio::time::sleep(Duration::from_secs(attempt.into())).await;
+    }
+
+    // The recorded branch may exist only on one of the other remotes
+    let timeouts = configured_timeouts(path, backend).await?;
+    for other in repo.remotes.iter().filter(|r| r.name != remote.name) {
+        backend.add_remote(path, other.name.as_str(), other.url.as_str()).await?;
+
+        let timeout = FetchTimeouts::default().get(other.name.as_str(), &timeouts);
+        match backend.fetch(path, other.name.as_str(), timeout).await? {
+            FetchOutcome::Fetched | FetchOutcome::Skipped => (),
+            FetchOutcome::TimedOut => return Err(format!("fetching remote '{}' timed out", other.name)),
+            FetchOutcome::AuthFailed(e) | FetchOutcome::Failed(e) => {
+                return Err(format!("could not fetch remote '{}': {e}", other.name));
+            }
         }
     }
 
-    // Assign the info to the final output only if there are any pending changes
-    if !status_output.is_empty() || !remotes_output.is_empty() {
-        final_output = format!("\r{}\n{status_output}{remotes_output}", repo.bold());
+    let current = match backend.state(path).await?.head {
+        Head::Branch(branch) => Some(branch),
+        Head::Detached(_) => None
+    };
+
+    match (&repo.branch, repo.tags.first()) {
+        (Some(branch), _) if current.as_ref() != Some(branch) => backend.checkout(path, branch.as_str()).await?,
+        (None, Some(tag)) => backend.checkout(path, tag.as_str()).await?,
+        _ => ()
     }
 
-    Ok(final_output)
+    Ok(ImportOutcome::Cloned)
 }
 
-// Obtains the output of `git status` for a given repository
-fn repo_status(repo: &str) -> Result<String, String> {
-    let mut output = String::new();
+// Verifies an existing manifest repository, adds its missing remotes and updates
+// the URLs of the changed ones, reports a different branch without switching it
+async fn reconcile_repo(path: &str, repo: &ManifestRepo, backend: &dyn GitBackend) -> Result<ImportOutcome, String> {
+    if !backend.is_repo(path).await? {
+        return Err(String::from("the path exists, but isn't a git repository"));
+    }
 
-    // Get the shorten output of `git status`
-    let git_status_out = Command::new("git")
-        .args(["-C", repo, "status", "-s"])
-        .stderr(Stdio::null())
-        .output()
-        .map_err(|e| format!("git: {e}"))?
-        .stdout;
-    let git_status_str = String::from_utf8_lossy(git_status_out.as_slice());
+    let mut changes = Vec::new();
 
-    // Push the details to the output only if
-    // `git status` didn't return an empty string
-    if !git_status_str.is_empty() {
-        for line in git_status_str.lines() {
-            writeln!(output, "  {}", line.trim())
-                .map_err(|e| e.to_string())?;
+    let remotes = backend.remotes(path).await?;
+    for remote in &repo.remotes {
+        if !remotes.contains(&remote.name) {
+            backend.add_remote(path, remote.name.as_str(), remote.url.as_str()).await?;
+            changes.push(format!("added remote '{}'", remote.name));
         }
+        else if backend.remote_url(path, remote.name.as_str()).await?.as_ref() != Some(&remote.url) {
+            backend.set_remote_url(path, remote.name.as_str(), remote.url.as_str()).await?;
+            changes.push(format!("changed the URL of remote '{}'", remote.name));
+        }
+    }
+
+    if let Some(branch) = &repo.branch
+        && let Head::Branch(current) = backend.state(path).await?.head
+        && &current != branch {
+        changes.push(format!("on branch {current} instead of {branch}"));
+    }
+
+    Ok(ImportOutcome::Existing(changes))
+}
+
+// Inspects the repositories concurrently while showing the progress, or
+// only fetches their remotes if `fetch_only` is set, returns the reports
+// in the order the repositories have been given in
+async fn collect_reports(repos: Vec<String>, options: &CheckOptions, fetch_only: bool, backend: Arc<dyn GitBackend>) -> Result<Vec<RepoReport>, String> {
+    // Handler for async progress bars, drawn only on terminals
+    // to keep control characters out of pipes and files
+    let multi_prog = if options.progress == Progress::None || !Term::stderr().is_term() {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    }
+    else {
+        MultiProgress::new()
+    };
+
+    // Count the inspected repositories on a single bar if requested
+    let overall = if options.progress == Progress::Bar {
+        let bar = multi_prog.add(ProgressBar::new(repos.len() as u64));
+        bar.set_style(ProgressStyle::with_template("{bar:40} {pos}/{len} repos checked").map_err(|e| e.to_string())?);
+        Some(bar)
+    }
+    else {
+        None
+    };
+
+    // Limit the number of repositories inspected
+    // and remotes fetched at the same time
+    let jobs = Arc::new(Semaphore::new(options.jobs.max(1)));
+    let fetch_jobs = Arc::new(Semaphore::new(options.fetch_jobs.max(1)));
+
+    let options = Arc::new(options.clone());
+
+    // Create an async task for each repo
+    let mut tasks = Vec::new();
+    for repo in repos {
+        let multi_prog_clone = multi_prog.clone();
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
+        let fetch_jobs_clone = Arc::clone(&fetch_jobs);
+        let options_clone = Arc::clone(&options);
+        let overall_clone = overall.clone();
+
+        tasks.push(tokio::spawn(async move {
+            // Wait for a free slot before starting the inspection
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{repo}' semaphore acquire"));
+
+            let spinner = (options_clone.progress == Progress::Spinner).then(|| {
+                let spinner = multi_prog_clone.add(ProgressBar::new_spinner());
+                spinner.set_message(repo.bold().to_string());
+                spinner.enable_steady_tick(Duration::from_millis(SPINNER_TICK));
+                spinner
+            });
+
+            // Keep the errors for the final report instead of
+            // leaving them behind in the spinner messages
+            let report = if fetch_only {
+                fetch_repo(repo.as_str(), &options_clone, &fetch_jobs_clone, backend_clone.as_ref()).await
+            }
+            else {
+                inspect_repo(repo.as_str(), &options_clone, &fetch_jobs_clone, backend_clone.as_ref()).await
+            };
+            let report = report.unwrap_or_else(|e| RepoReport::failed(repo.as_str(), e));
+
+            if let Some(spinner) = spinner {
+                spinner.finish_and_clear();
+            }
+            if let Some(overall) = overall_clone {
+                overall.inc(1);
+            }
+
+            report
+        }));
     }
 
-    Ok(output)
+    // Execute the tasks, collecting the reports in the order the
+    // repositories were given regardless of which finishes first
+    let mut reports = Vec::new();
+    for task in tasks {
+        reports.push(task.await.map_err(|e| e.to_string())?);
+    }
+
+    if let Some(overall) = overall {
+        overall.finish_and_clear();
+    }
+
+    Ok(reports)
 }
 
-// Retrieves the difference in the number of commits between a given branch and remotes,
-// formats the output and returns it in a String
-fn remotes_diff(repo: &str, branch: &str, remotes: Vec<&str>) -> Result<String, String> {
-    let mut output = String::new();
+// Retrieves the status of a given repository and the
+// difference in the number of commits between each branch
+// and the respective remote, skips the inspections none
+// of the selected conditions depend on, returns a report
+// with the results of each operation
+async fn inspect_repo(repo: &str, options: &CheckOptions, fetch_jobs: &Semaphore, backend: &dyn GitBackend) -> Result<RepoReport, String> {
+    let only = options.only.as_slice();
+
+    // The times of the last commit and modification are
+    // looked up only if they're going to be shown or sorted by
+    let mut report = if activity_shown(options) {
+        inspect_activity(repo, backend).await?
+    }
+    else {
+        RepoReport {
+            path: repo.to_string(),
+            last_fetch: backend.last_fetch(repo).await?.and_then(timestamp),
+            ..RepoReport::default()
+        }
+    };
+    report.state = Some(backend.state(repo).await?);
+
+    if Condition::Dirty.is_selected(only) {
+        report.status = StatusReport::new(backend.status(repo).await?);
+    }
+
+    if let Some(max_age) = options.stale_branches
+        && Condition::StaleBranch.is_selected(only) {
+        let current = match report.state.as_ref().map(|s| &s.head) {
+            Some(Head::Branch(branch)) => Some(branch.as_str()),
+            _ => None
+        };
+
+        report.stale_branches = stale_branches(repo, current, max_age, backend).await?;
+    }
+
+    // Get the list of remotes, needed to tell the repositories without any
+    let remotes = backend.remotes(repo).await?;
+
+    // Get the list of branches if they are going to be compared
+    let compare = [Condition::Unpushed, Condition::Behind, Condition::MissingUpstream]
+        .into_iter()
+        .any(|c| c.is_selected(only));
+    let branches = if compare {
+        backend.branches(repo).await?
+    }
+    else {
+        Vec::new()
+    };
+
+    // Leave if there are no branches to compare, unless
+    // the outcome of fetching is all that matters
+    let fetch_wanted = if compare {
+        !branches.is_empty()
+    }
+    else {
+        Condition::FetchFailed.is_selected(only)
+    };
+    if !fetch_wanted {
+        report.remotes = remotes.into_iter()
+            .map(|name| RemoteReport { name, fetch: FetchOutcome::Skipped })
+            .collect();
+
+        return Ok(report)
+    }
+
+    // Determine the age of the remote data
+    let fetch_age = age_of(report.last_fetch);
+
+    let fetch = match options.fetch {
+        FetchMode::Always => true,
+        FetchMode::Never => false,
+        FetchMode::IfStale(max_age) => fetch_age.is_none_or(|age| age > max_age)
+    };
+
+    // Fetch the latest data from remote repositories
+    report.remotes = if fetch {
+        fetch_remotes(repo, remotes.as_slice(), options, fetch_jobs, backend).await?
+    }
+    else {
+        remotes.iter()
+            .map(|name| RemoteReport { name: name.clone(), fetch: FetchOutcome::Skipped })
+            .collect()
+    };
+
+    // Fetching has just updated the time of the last fetch
+    if fetch {
+        report.last_fetch = backend.last_fetch(repo).await?.and_then(timestamp);
+    }
 
+    // Inspect each branch
+    for branch in branches {
+        report.branches.push(branch_diff(repo, branch, remotes.as_slice(), options.all_remotes, backend).await?);
+    }
+
+    Ok(report)
+}
+
+// Checks if the activity of the repositories is going to be shown or sorted by
+fn activity_shown(options: &CheckOptions) -> bool {
+    options.verbose || options.format == OutputFormat::Json || options.sort == Some(SortKey::LastCommit)
+}
+
+// Fetches only the remotes of a given repository, skipping
+// everything else, returns a report with the outcome of each fetch
+async fn fetch_repo(repo: &str, options: &CheckOptions, fetch_jobs: &Semaphore, backend: &dyn GitBackend) -> Result<RepoReport, String> {
+    let remotes = backend.remotes(repo).await?;
+
+    Ok(RepoReport {
+        path: repo.to_string(),
+        remotes: fetch_remotes(repo, remotes.as_slice(), options, fetch_jobs, backend).await?,
+        ..RepoReport::default()
+    })
+}
+
+// Fetches the given remotes of a repository, at most
+// `fetch_jobs` at once across all repositories
+async fn fetch_remotes(repo: &str, remotes: &[String], options: &CheckOptions, fetch_jobs: &Semaphore, backend: &dyn GitBackend) -> Result<Vec<RemoteReport>, String> {
+    // Timeouts configured in git apply unless given explicitly
+    let configured_timeouts = configured_timeouts(repo, backend).await?;
+
+    let mut reports = Vec::new();
     for remote in remotes {
-        let remote_branch = format!("{remote}/{branch}");
-
-        // Get the difference between the remote and local branch
-        let git_rev_list_out = Command::new("git")
-            .args([
-                "-C",
-                repo,
-                "rev-list",
-                "--left-right",
-                "--count",
-                format!("{remote_branch}...{branch}").as_str()
-            ])
-            .stderr(Stdio::null())
-            .output()
-            .map_err(|e| format!("git: {e}"))?
-            .stdout;
-        let git_rev_list_str = String::from_utf8_lossy(git_rev_list_out.as_slice());
-
-        // Skip if the remote branch doesn't exist
-        if git_rev_list_str.is_empty() {
-            writeln!(output, "    missing from '{remote}' remote")
-                .map_err(|e| e.to_string())?;
+        let timeout = options.fetch_timeouts.get(remote, &configured_timeouts);
+        let _permit = fetch_jobs.acquire().await.map_err(|e| e.to_string())?;
+
+        reports.push(RemoteReport {
+            name: remote.clone(),
+            fetch: backend.fetch(repo, remote, timeout).await?
+        });
+    }
+
+    Ok(reports)
+}
+
+// Finds the local branches merged into the default branch, with a gone upstream
+// or without commits for longer than max_age, skips the default and current branch
+async fn stale_branches(repo: &str, current: Option<&str>, max_age: Duration, backend: &dyn GitBackend) -> Result<Vec<StaleBranch>, String> {
+    let default = backend.default_branch(repo).await?;
+    let merged = match &default {
+        Some(default) => backend.merged_branches(repo, default).await?,
+        None => Vec::new()
+    };
+
+    let mut stale = Vec::new();
+    for branch in backend.branches(repo).await? {
+        if Some(&branch.name) == default.as_ref() || Some(branch.name.as_str()) == current {
             continue;
         }
 
-        // Retrieve the commit numbers
-        let git_rev_list_vec: Vec<&str> = git_rev_list_str.split_whitespace().collect();
+        let mut reasons = Vec::new();
 
-        // Parse the numbers
-        let (behind, ahead): (u32, u32) = (
-            git_rev_list_vec[0].parse().unwrap(),
-            git_rev_list_vec[1].parse().unwrap()
-        );
+        if let Some(default) = &default
+            && merged.contains(&branch.name) {
+            reasons.push(StaleReason::Merged { into: default.clone() });
+        }
 
-        // Push only relevant info to the output
+        // `None` means the upstream doesn't exist anymore
+        if let Some(upstream) = &branch.upstream
+            && backend.ahead_behind(repo, branch.name.as_str(), upstream.name.as_str()).await?.is_none() {
+            reasons.push(StaleReason::Gone { upstream: upstream.name.clone() });
+        }
 
-        if behind == 0 && ahead == 0 {
-            continue;
+        let age = branch.last_commit.map(|t| SystemTime::now().duration_since(t).unwrap_or_default());
+        if let Some(age) = age
+            && age > max_age {
+            reasons.push(StaleReason::Inactive { age: age.as_secs() });
         }
 
-        if ahead == 0 {
-            writeln!(output, "    {behind} commit(s) behind {remote_branch}")
-                .map_err(|e| e.to_string())?;
-            continue;
+        if !reasons.is_empty() {
+            stale.push(StaleBranch {
+                name: branch.name,
+                reasons
+            });
         }
+    }
+
+    Ok(stale)
+}
+
+// Core functionality of the `branches prune` command
+pub async fn exec_prune_branches(repos: &[String], max_age: Duration, force: bool, yes: bool, backend: &dyn GitBackend) -> Result<(), String> {
+    // List the stale branches of every repository first,
+    // unmerged ones are deleted only if forced
+    let mut candidates = Vec::new();
+    let mut kept = 0;
+    for repo in repos {
+        let current = match backend.state(repo).await?.head {
+            Head::Branch(branch) => Some(branch),
+            Head::Detached(_) => None
+        };
 
-        if behind == 0 {
-            writeln!(output, "    {ahead} commit(s) ahead of {remote_branch}")
-                .map_err(|e| e.to_string())?;
+        let stale = stale_branches(repo, current.as_deref(), max_age, backend).await?;
+        if stale.is_empty() {
             continue;
         }
 
-        writeln!(output, "    {ahead} commit(s) ahead of, {behind} commit(s) behind {remote_branch}")
-            .map_err(|e| e.to_string())?;
+        println!("{}", repo.bold());
+        let (deleted, unmerged): (Vec<StaleBranch>, Vec<StaleBranch>) = stale
+            .into_iter()
+            .partition(|b| force || b.is_merged());
+        for branch in &deleted {
+            println!("  {branch}");
+        }
+        for branch in &unmerged {
+            println!("  {}", format!("{branch} (not deleted, unmerged)").dimmed());
+        }
+
+        kept += unmerged.len();
+        if !deleted.is_empty() {
+            candidates.push((repo, deleted));
+        }
+    }
+
+    if kept > 0 {
+        println!("\n{APP_NAME}: Kept {kept} unmerged branch(es), use --force to delete them as well");
+    }
+
+    let count: usize = candidates.iter().map(|(_, stale)| stale.len()).sum();
+    if count == 0 {
+        if kept == 0 {
+            println!("{APP_NAME}: No stale branches found");
+        }
+        return Ok(());
+    }
+
+    if !yes && !confirm(format!("\nDelete {count} branch(es)?").as_str())? {
+        return Ok(());
     }
 
-    // Put the local branch name at the beginning if the output isn't empty
-    if !output.is_empty() {
-        output.insert_str(0, format!("  {}:\n", branch.underline()).as_str());
+    // Branches are deleted forcibly, since `git branch -d` checks them against
+    // HEAD or their upstream instead of the default branch checked above
+    let mut failed = 0;
+    for (repo, stale) in candidates {
+        for branch in stale {
+            if let Err(e) = backend.delete_branch(repo, branch.name.as_str(), true).await {
+                eprintln!("{APP_NAME}: {repo}: {e}");
+                failed += 1;
+            }
+        }
     }
 
-    Ok(output)
+    if failed > 0 {
+        return Err(format!("Could not delete {failed} branch(es)"));
+    }
+
+    println!("{APP_NAME}: Deleted {count} branch(es)");
+
+    Ok(())
+}
+
+// Retrieves the times of the last commit, local modification
+// and fetch of a given repository, returns a report with them
+async fn inspect_activity(repo: &str, backend: &dyn GitBackend) -> Result<RepoReport, String> {
+    let last_commit = backend.last_commit(repo).await?;
+
+    Ok(RepoReport {
+        path: repo.to_string(),
+        last_commit: last_commit.as_ref().and_then(|c| timestamp(c.time)),
+        last_commit_author: last_commit.map(|c| c.author),
+        last_modified: backend.last_modified(repo).await?.and_then(timestamp),
+        last_fetch: backend.last_fetch(repo).await?.and_then(timestamp),
+        ..RepoReport::default()
+    })
+}
+
+// Core functionality of the `list` command with activity information,
+// lists only the repositories inactive for longer than `stale` if given
+pub async fn exec_list_activity(repos: Vec<String>, sort: Option<ListSort>, stale: Option<Duration>, jobs: usize, backend: Arc<dyn GitBackend>) -> Result<(), String> {
+    let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+
+    // Inspect the repositories concurrently, at most `jobs`
+    // at once, keeping their order
+    let mut tasks = Vec::new();
+    for repo in repos {
+        let backend_clone = Arc::clone(&backend);
+        let jobs_clone = Arc::clone(&jobs);
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = jobs_clone
+                .acquire()
+                .await
+                .unwrap_or_else(|_| panic!("'{repo}' semaphore acquire"));
+
+            inspect_activity(repo.as_str(), backend_clone.as_ref())
+                .await
+                .unwrap_or_else(|e| RepoReport::failed(repo.as_str(), e))
+        }));
+    }
+
+    let mut reports = Vec::new();
+    for task in tasks {
+        reports.push(task.await.map_err(|e| e.to_string())?);
+    }
+
+    let (failed, mut reports): (Vec<RepoReport>, Vec<RepoReport>) = reports
+        .into_iter()
+        .partition(|r| r.error.is_some());
+
+    // Repositories without any activity at all are always stale
+    if let Some(stale) = stale {
+        let now = timestamp(SystemTime::now()).unwrap_or_default();
+        reports.retain(|r| r.last_activity().is_none_or(|t| now.saturating_sub(t) > stale.as_secs()));
+    }
+
+    match sort {
+        Some(ListSort::Path) => reports.sort_by(|a, b| a.path.cmp(&b.path)),
+        Some(ListSort::Activity) => reports.sort_by_key(|r| Reverse(r.last_activity())),
+        None => ()
+    }
+
+    print!("{}", report::activity(&reports));
+    eprint!("{}", stderr_text(report::errors(&failed).as_str()));
+
+    Ok(())
+}
+
+// Converts a point in time to seconds since the Unix epoch
+fn timestamp(time: SystemTime) -> Option<u64> {
+    time.duration_since(SystemTime::UNIX_EPOCH).ok().map(|t| t.as_secs())
+}
+
+// Collects the fetch timeouts configured in git for a given repository
+async fn configured_timeouts(repo: &str, backend: &dyn GitBackend) -> Result<FetchTimeouts, String> {
+    let mut timeouts = FetchTimeouts::default();
+    for (remote, timeout) in backend.fetch_timeouts(repo).await? {
+        timeouts.set(remote, timeout);
+    }
+
+    Ok(timeouts)
+}
+
+// Obtains the time elapsed since the last fetch, ignoring clock skew
+fn age_of(last_fetch: Option<u64>) -> Option<Duration> {
+    let now = timestamp(SystemTime::now()).unwrap_or_default();
+    last_fetch.map(|t| Duration::from_secs(now.saturating_sub(t)))
+}
+
+// Retrieves the difference in the number of commits between a given branch and its
+// upstream and push destination, or the same-named branch on every remote if requested
+async fn branch_diff(repo: &str, branch: Branch, remotes: &[String], all_remotes: bool, backend: &dyn GitBackend) -> Result<BranchReport, String> {
+    let upstream = match &branch.upstream {
+        Some(upstream) => Some(divergence(repo, branch.name.as_str(), upstream, backend).await?),
+        None => None
+    };
+
+    // Compare with the push destination only if it's somewhere else than the upstream
+    let push = match &branch.push {
+        Some(push) if branch.upstream.as_ref() != Some(push) => Some(divergence(repo, branch.name.as_str(), push, backend).await?),
+        _ => None
+    };
+
+    let mut divergences = Vec::new();
+
+    if all_remotes {
+        for remote in remotes {
+            let remote_branch = RemoteBranch {
+                remote: remote.clone(),
+                name: format!("{remote}/{}", branch.name)
+            };
+
+            divergences.push(divergence(repo, branch.name.as_str(), &remote_branch, backend).await?);
+        }
+    }
+
+    Ok(BranchReport {
+        name: branch.name,
+        upstream,
+        push,
+        remotes: divergences
+    })
+}
+
+// Retrieves the difference in the number of commits between a local and remote branch
+async fn divergence(repo: &str, branch: &str, remote_branch: &RemoteBranch, backend: &dyn GitBackend) -> Result<Divergence, String> {
+    // `None` means the remote branch doesn't exist
+    let counts = backend.ahead_behind(repo, branch, remote_branch.name.as_str())
+        .await?
+        .map(|(behind, ahead)| AheadBehind { ahead, behind });
+
+    Ok(Divergence {
+        remote: remote_branch.remote.clone(),
+        remote_branch: remote_branch.name.clone(),
+        counts
+    })
 }
@@ -0,0 +1,782 @@
+//! Git backends used by the core module
+
+#![allow(clippy::missing_errors_doc)]
+
+use crate::utils::parse_duration;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::process::Stdio;
+
+use tokio::process::Command;
+use tokio::time;
+use tokio::task;
+use async_trait::async_trait;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+/// Result of fetching a single remote
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "state", content = "message", rename_all = "snake_case")]
+pub enum FetchOutcome {
+    /// The remote has been fetched successfully
+    Fetched,
+    /// The remote hasn't been fetched on purpose
+    Skipped,
+    /// Fetching didn't finish in time and has been killed
+    TimedOut,
+    /// The remote rejected or required credentials, with the given error message
+    AuthFailed(String),
+    /// Fetching failed with the given error message
+    Failed(String)
+}
+
+/// Result of pushing a single branch
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PushOutcome {
+    /// The branch has been pushed successfully
+    Pushed,
+    /// The remote refused the update with the given reason, e.g. because it isn't a fast-forward
+    Rejected(String),
+    /// Pushing failed with the given error message
+    Failed(String)
+}
+
+/// Local branch along with the remote branches it's configured to work with
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Branch {
+    pub name: String,
+    /// Branch set by `branch.<name>.remote` and `branch.<name>.merge`
+    pub upstream: Option<RemoteBranch>,
+    /// Branch `git push` would update
+    pub push: Option<RemoteBranch>,
+    /// Committer date of the last commit on the branch
+    pub last_commit: Option<SystemTime>
+}
+
+/// Branch on a remote, `remote` is `.` for local upstreams
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RemoteBranch {
+    pub remote: String,
+    /// Short name of the ref, e.g. `origin/main`
+    pub name: String
+}
+
+/// Ignored files and directories at least this big get reported
+pub const LARGE_IGNORED_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Entry of `git status`
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct FileStatus {
+    pub path: String,
+    /// Two-letter code as in `git status -s`: the state in the index followed by
+    /// the state in the working tree, `??` for untracked and `!!` for ignored
+    pub code: String,
+    /// Original path of a renamed or copied file
+    pub renamed_from: Option<String>,
+    /// Whether the file has merge conflicts
+    pub conflicted: bool
+}
+
+/// What `HEAD` points to
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Head {
+    /// Name of the checked out branch
+    Branch(String),
+    /// Abbreviated hash of the checked out commit
+    Detached(String)
+}
+
+/// Operation left unfinished in the working tree
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Rebase,
+    Am,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect
+}
+
+/// Committer date and author of a commit
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Commit {
+    pub time: SystemTime,
+    pub author: String
+}
+
+/// Commit listed by `git log`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LogEntry {
+    /// Abbreviated hash
+    pub hash: String,
+    /// Committer date
+    pub time: SystemTime,
+    /// Committer date as `YYYY-MM-DD HH:MM` in local time
+    pub date: String,
+    pub author: String,
+    pub subject: String
+}
+
+/// Line of a tracked file matching a `git grep` pattern
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct GrepMatch {
+    /// Path relative to the root of the repository
+    pub path: String,
+    pub line: usize,
+    pub text: String
+}
+
+/// State of the working tree apart from its changes
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct RepoState {
+    pub head: Head,
+    pub operation: Option<Operation>,
+    pub stashes: usize
+}
+
+// Fragments of git and ssh error messages caused by missing or rejected credentials
+const AUTH_ERRORS: [&str; 7] = [
+    "Authentication failed",
+    "could not read Username",
+    "could not read Password",
+    "terminal prompts disabled",
+    "Permission denied (",
+    "Host key verification failed",
+    "HTTP Basic: Access denied"
+];
+
+/// Set of git operations the core module relies on,
+/// implement it to plug in an alternative backend
+#[async_trait]
+pub trait GitBackend: Send + Sync {
+    /// Checks if a given path is a git repository
+    async fn is_repo(&self, path: &str) -> Result<bool, String>;
+
+    /// Returns the changed and untracked files of a given repository, along
+    /// with ignored ones taking at least `LARGE_IGNORED_SIZE` bytes
+    async fn status(&self, repo: &str) -> Result<Vec<FileStatus>, String>;
+
+    /// Returns all local branches with their upstream and push branches
+    async fn branches(&self, repo: &str) -> Result<Vec<Branch>, String>;
+
+    /// Returns the state of `HEAD`, unfinished operations and the number of stashes
+    async fn state(&self, repo: &str) -> Result<RepoState, String>;
+
+    /// Returns the names of all remotes
+    async fn remotes(&self, repo: &str) -> Result<Vec<String>, String>;
+
+    /// Fetches the latest data from a given remote, gives up after the timeout
+    async fn fetch(&self, repo: &str, remote: &str, timeout: Duration) -> Result<FetchOutcome, String>;
+
+    /// Returns the time of the last fetch, or `None` if the repository has never been fetched
+    async fn last_fetch(&self, repo: &str) -> Result<Option<SystemTime>, String>;
+
+    /// Returns the fetch timeouts configured with `conform.fetchTimeout` and
+    /// `remote.<name>.conformFetchTimeout`, along with the remote names
+    async fn fetch_timeouts(&self, repo: &str) -> Result<Vec<(Option<String>, Duration)>, String>;
+
+    /// Returns the last commit on `HEAD`, or `None` if there are no commits yet
+    async fn last_commit(&self, repo: &str) -> Result<Option<Commit>, String>;
+
+    /// Returns the latest modification time of the index and the changed
+    /// or untracked files, or `None` if none of them exists
+    async fn last_modified(&self, repo: &str) -> Result<Option<SystemTime>, String>;
+
+    /// Returns the number of commits `(behind, ahead)` the local branch
+    /// is relative to the remote one, or `None` if the remote branch doesn't exist
+    async fn ahead_behind(&self, repo: &str, branch: &str, remote_branch: &str) -> Result<Option<(u32, u32)>, String>;
+
+    /// Returns the local branch the remotes point their `HEAD` at,
+    /// or a local `main` or `master` branch if they don't
+    async fn default_branch(&self, repo: &str) -> Result<Option<String>, String>;
+
+    /// Returns the names of the local branches fully merged into the given one
+    async fn merged_branches(&self, repo: &str, base: &str) -> Result<Vec<String>, String>;
+
+    /// Deletes a local branch, even an unmerged one if `force` is set
+    async fn delete_branch(&self, repo: &str, branch: &str, force: bool) -> Result<(), String>;
+
+    /// Fast-forwards the current branch to the given one, fails if it isn't possible
+    async fn fast_forward(&self, repo: &str, target: &str) -> Result<(), String>;
+
+    /// Pushes a local branch to the given branch on a remote, never forcibly
+    async fn push(&self, repo: &str, branch: &str, remote: &str, remote_branch: &str) -> Result<PushOutcome, String>;
+
+    /// Returns the lines of the tracked text files in the working tree matching a pattern
+    async fn grep(&self, repo: &str, pattern: &str) -> Result<Vec<GrepMatch>, String>;
+
+    /// Returns the fetch URL of a given remote, or `None` if it has none
+    async fn remote_url(&self, repo: &str, remote: &str) -> Result<Option<String>, String>;
+
+    /// Returns the tags pointing at `HEAD`
+    async fn head_tags(&self, repo: &str) -> Result<Vec<String>, String>;
+
+    /// Clones a repository into a given path, naming the remote as given,
+    /// creates the missing parent directories
+    async fn clone(&self, url: &str, path: &str, remote: &str) -> Result<(), String>;
+
+    /// Adds a remote with the given URL
+    async fn add_remote(&self, repo: &str, remote: &str, url: &str) -> Result<(), String>;
+
+    /// Changes the URL of a given remote
+    async fn set_remote_url(&self, repo: &str, remote: &str, url: &str) -> Result<(), String>;
+
+    /// Checks out a branch, a remote branch of the same name if there is
+    /// no such local one, or any other revision with a detached `HEAD`
+    async fn checkout(&self, repo: &str, rev: &str) -> Result<(), String>;
+
+    /// Returns the commits on all local branches newer than `since` (any date
+    /// `git log --since` accepts), only the ones by a matching author if given
+    async fn log(&self, repo: &str, since: &str, author: Option<&str>) -> Result<Vec<LogEntry>, String>;
+}
+
+// Checks if a file or directory takes at least the given number of bytes,
+// stops walking the directory as soon as the limit is reached
+fn path_exceeds(path: &Path, limit: u64) -> bool {
+    let mut size = 0;
+
+    for entry in WalkDir::new(path).into_iter().filter_map(Result::ok) {
+        if let Ok(metadata) = entry.metadata()
+            && metadata.is_file() {
+            size += metadata.len();
+
+            if size >= limit {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Default backend, runs the `git` executable
+pub struct GitCli;
+
+impl GitCli {
+    // Runs git with given arguments in a repository
+    // and returns its standard output
+    async fn output(repo: &str, args: &[&str]) -> Result<String, String> {
+        let out = Command::new("git")
+            .args(["-C", repo])
+            .args(args)
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("git: {e}"))?
+            .stdout;
+
+        Ok(String::from_utf8_lossy(out.as_slice()).into_owned())
+    }
+
+    // Prepares git to run in a given repository without asking for
+    // credentials, passphrases or host key confirmations, so it fails
+    // right away instead
+    async fn non_interactive(repo: &str) -> Command {
+        let ssh_command = format!("{} -o BatchMode=yes", Self::ssh_command(repo).await);
+
+        let mut command = Command::new("git");
+        command
+            .args(["-C", repo, "-c", "core.askPass=", "-c", "credential.interactive=false"])
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GIT_SSH_COMMAND", ssh_command)
+            .env("GCM_INTERACTIVE", "never")
+            .env_remove("GIT_ASKPASS")
+            .env_remove("SSH_ASKPASS")
+            .stdin(Stdio::null());
+
+        command
+    }
+
+    // Returns the ssh command git would use in a given repository,
+    // following the precedence git itself gives to its settings
+    async fn ssh_command(repo: &str) -> String {
+        match env::var("GIT_SSH_COMMAND") {
+            Ok(cmd) if !cmd.trim().is_empty() => return cmd,
+            _ => ()
+        }
+
+        let configured = Self::output(repo, &["config", "core.sshCommand"]).await.unwrap_or_default();
+        if !configured.trim().is_empty() {
+            return configured.trim().to_string();
+        }
+
+        // Unlike the others, GIT_SSH names a program to run
+        // rather than a shell command
+        match env::var("GIT_SSH") {
+            Ok(program) if !program.trim().is_empty() => format!("'{}'", program.replace('\'', "'\\''")),
+            _ => String::from("ssh")
+        }
+    }
+
+    // Runs git in a given repository for its side effects,
+    // returns the error message if the command fails
+    async fn run(repo: &str, args: &[&str]) -> Result<(), String> {
+        let out = Command::new("git")
+            .args(["-C", repo])
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("git: {e}"))?;
+
+        if out.status.success() {
+            Ok(())
+        }
+        else {
+            Err(String::from_utf8_lossy(out.stderr.as_slice()).trim().to_string())
+        }
+    }
+}
+
+#[async_trait]
+impl GitBackend for GitCli {
+    async fn is_repo(&self, path: &str) -> Result<bool, String> {
+        let git_status = Command::new("git")
+            .args(["-C", path, "status"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| format!("git: {e}"))?;
+
+        Ok(git_status.success())
+    }
+
+    async fn status(&self, repo: &str) -> Result<Vec<FileStatus>, String> {
+        let git_status_str = Self::output(repo, &["status", "--porcelain=v2", "-z", "--ignored"]).await?;
+        let mut entries = git_status_str.split('\0').filter(|e| !e.is_empty());
+
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next() {
+            let unexpected = || format!("git status: unexpected output '{entry}'");
+
+            // Paths are the last field of each entry and may contain spaces
+            let fields: Vec<&str> = match entry.chars().next() {
+                Some('1') => entry.splitn(9, ' ').collect(),
+                Some('2') => entry.splitn(10, ' ').collect(),
+                Some('u') => entry.splitn(11, ' ').collect(),
+                Some('?' | '!') => entry.splitn(2, ' ').collect(),
+                _ => return Err(unexpected())
+            };
+            let path = (*fields.last().ok_or_else(unexpected)?).to_string();
+
+            let file = match fields[0] {
+                "?" => FileStatus {
+                    path,
+                    code: String::from("??"),
+                    renamed_from: None,
+                    conflicted: false
+                },
+                "!" => {
+                    // Skip ignored entries that aren't worth mentioning
+                    let full_path = Path::new(repo).join(&path);
+                    let large = task::spawn_blocking(move || path_exceeds(full_path.as_path(), LARGE_IGNORED_SIZE))
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    if !large {
+                        continue;
+                    }
+
+                    FileStatus {
+                        path,
+                        code: String::from("!!"),
+                        renamed_from: None,
+                        conflicted: false
+                    }
+                },
+                kind => FileStatus {
+                    path,
+                    code: fields.get(1).ok_or_else(unexpected)?.replace('.', " "),
+                    // Renamed and copied entries are followed by the original path
+                    renamed_from: if kind == "2" { entries.next().map(String::from) } else { None },
+                    conflicted: kind == "u"
+                }
+            };
+
+            files.push(file);
+        }
+
+        Ok(files)
+    }
+
+    async fn branches(&self, repo: &str) -> Result<Vec<Branch>, String> {
+        let format = "--format=%(refname:short)%09%(upstream:short)%09%(upstream:remotename)%09%(push:short)%09%(push:remotename)%09%(committerdate:unix)";
+
+        Ok(Self::output(repo, &["for-each-ref", format, "refs/heads"]).await?
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+
+                // Empty fields mean the remote branch isn't configured
+                let remote_branch = |name: Option<&&str>, remote: Option<&&str>| {
+                    name.filter(|n| !n.is_empty()).map(|n| RemoteBranch {
+                        remote: remote.map_or_else(String::new, ToString::to_string),
+                        name: (*n).to_string()
+                    })
+                };
+
+                Branch {
+                    name: fields[0].to_string(),
+                    upstream: remote_branch(fields.get(1), fields.get(2)),
+                    push: remote_branch(fields.get(3), fields.get(4)),
+                    last_commit: fields.get(5)
+                        .and_then(|secs| secs.parse().ok())
+                        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                }
+            })
+            .collect())
+    }
+
+    async fn state(&self, repo: &str) -> Result<RepoState, String> {
+        let branch = Self::output(repo, &["symbolic-ref", "--quiet", "--short", "HEAD"]).await?;
+        let head = if branch.trim().is_empty() {
+            Head::Detached(Self::output(repo, &["rev-parse", "--short", "HEAD"]).await?.trim().to_string())
+        }
+        else {
+            Head::Branch(branch.trim().to_string())
+        };
+
+        // Unfinished operations leave their traces in the git directory
+        let git_dir = Self::output(repo, &["rev-parse", "--absolute-git-dir"]).await?;
+        let git_dir = Path::new(git_dir.trim());
+        let operation = [
+            ("rebase-merge", Operation::Rebase),
+            ("rebase-apply/applying", Operation::Am),
+            ("rebase-apply", Operation::Rebase),
+            ("MERGE_HEAD", Operation::Merge),
+            ("CHERRY_PICK_HEAD", Operation::CherryPick),
+            ("REVERT_HEAD", Operation::Revert),
+            ("BISECT_LOG", Operation::Bisect)
+        ]
+        .into_iter()
+        .find(|(file, _)| git_dir.join(file).exists())
+        .map(|(_, op)| op);
+
+        let stashes = Self::output(repo, &["stash", "list"]).await?.lines().count();
+
+        Ok(RepoState {
+            head,
+            operation,
+            stashes
+        })
+    }
+
+    async fn remotes(&self, repo: &str) -> Result<Vec<String>, String> {
+        Ok(Self::output(repo, &["remote"]).await?
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    async fn fetch(&self, repo: &str, remote: &str, timeout: Duration) -> Result<FetchOutcome, String> {
+        // Dropping the future on timeout kills the process
+        let git_fetch = Self::non_interactive(repo).await
+            .args(["fetch", remote])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+
+        let Ok(output) = time::timeout(timeout, git_fetch).await else {
+            return Ok(FetchOutcome::TimedOut);
+        };
+        let output = output.map_err(|e| format!("git fetch: {e}"))?;
+
+        if output.status.success() {
+            return Ok(FetchOutcome::Fetched);
+        }
+
+        let stderr = String::from_utf8_lossy(output.stderr.as_slice()).trim().to_string();
+
+        if AUTH_ERRORS.iter().any(|e| stderr.contains(e)) {
+            Ok(FetchOutcome::AuthFailed(stderr))
+        }
+        else {
+            Ok(FetchOutcome::Failed(stderr))
+        }
+    }
+
+    async fn last_fetch(&self, repo: &str) -> Result<Option<SystemTime>, String> {
+        let git_dir = Self::output(repo, &["rev-parse", "--git-common-dir"]).await?;
+
+        // FETCH_HEAD gets rewritten on every fetch, so its
+        // modification time tells when the last one happened
+        let fetch_head = Path::new(repo).join(git_dir.trim()).join("FETCH_HEAD");
+        match fs::metadata(&fetch_head) {
+            Ok(metadata) => Ok(Some(metadata.modified().map_err(|e| format!("{}: {e}", fetch_head.display()))?)),
+            Err(_) => Ok(None)
+        }
+    }
+
+    async fn fetch_timeouts(&self, repo: &str) -> Result<Vec<(Option<String>, Duration)>, String> {
+        // Section and variable names come out in lower case, remote names don't
+        let git_config_str = Self::output(repo, &[
+            "config",
+            "--get-regexp",
+            r"^(conform\.fetchtimeout|remote\..+\.conformfetchtimeout)$"
+        ]).await?;
+
+        let mut timeouts = Vec::new();
+        for line in git_config_str.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let remote = key.strip_prefix("remote.")
+                .and_then(|k| k.strip_suffix(".conformfetchtimeout"))
+                .map(String::from);
+            let timeout = parse_duration(value).map_err(|e| format!("{key}: {e}"))?;
+
+            timeouts.push((remote, timeout));
+        }
+
+        Ok(timeouts)
+    }
+
+    async fn last_commit(&self, repo: &str) -> Result<Option<Commit>, String> {
+        let git_log_str = Self::output(repo, &["log", "-1", "--format=%ct%x09%an"]).await?;
+
+        // There are no commits on `HEAD` yet
+        if git_log_str.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let unexpected = || format!("git log: unexpected output '{}'", git_log_str.trim());
+
+        let (secs, author) = git_log_str.trim_end().split_once('\t').ok_or_else(unexpected)?;
+        let secs: u64 = secs.parse().map_err(|_| unexpected())?;
+
+        Ok(Some(Commit {
+            time: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            author: author.to_string()
+        }))
+    }
+
+    async fn last_modified(&self, repo: &str) -> Result<Option<SystemTime>, String> {
+        let index = Self::output(repo, &["rev-parse", "--path-format=absolute", "--git-path", "index"]).await?;
+        let files = Self::output(repo, &["ls-files", "-z", "--modified", "--others", "--exclude-standard"]).await?;
+
+        // The index gets rewritten by most operations
+        // changing the working tree, look at it as well
+        let mut paths = vec![PathBuf::from(index.trim())];
+        paths.extend(files.split('\0').filter(|f| !f.is_empty()).map(|f| Path::new(repo).join(f)));
+
+        task::spawn_blocking(move || {
+            paths.iter()
+                .filter_map(|p| fs::symlink_metadata(p).and_then(|m| m.modified()).ok())
+                .max()
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    async fn ahead_behind(&self, repo: &str, branch: &str, remote_branch: &str) -> Result<Option<(u32, u32)>, String> {
+        let git_rev_list_str = Self::output(repo, &[
+            "rev-list",
+            "--left-right",
+            "--count",
+            format!("{remote_branch}...{branch}").as_str()
+        ]).await?;
+
+        // The remote branch doesn't exist
+        if git_rev_list_str.is_empty() {
+            return Ok(None);
+        }
+
+        // Retrieve and parse the commit numbers
+        let counts: Vec<u32> = git_rev_list_str
+            .split_whitespace()
+            .map(|n| n.parse().map_err(|_| format!("git rev-list: unexpected output '{}'", git_rev_list_str.trim())))
+            .collect::<Result<_, _>>()?;
+
+        match counts.as_slice() {
+            [behind, ahead] => Ok(Some((*behind, *ahead))),
+            _ => Err(format!("git rev-list: unexpected output '{}'", git_rev_list_str.trim()))
+        }
+    }
+
+    async fn default_branch(&self, repo: &str) -> Result<Option<String>, String> {
+        let local: Vec<String> = Self::output(repo, &["for-each-ref", "--format=%(refname:short)", "refs/heads"]).await?
+            .lines()
+            .map(String::from)
+            .collect();
+
+        // Prefer the branches the remotes consider the default ones
+        let mut candidates = Vec::new();
+        for remote in self.remotes(repo).await? {
+            let head = Self::output(repo, &[
+                "symbolic-ref",
+                "--quiet",
+                "--short",
+                format!("refs/remotes/{remote}/HEAD").as_str()
+            ]).await?;
+
+            if let Some(branch) = head.trim().strip_prefix(format!("{remote}/").as_str()) {
+                candidates.push(branch.to_string());
+            }
+        }
+        candidates.extend([String::from("main"), String::from("master")]);
+
+        Ok(candidates.into_iter().find(|c| local.contains(c)))
+    }
+
+    async fn merged_branches(&self, repo: &str, base: &str) -> Result<Vec<String>, String> {
+        Ok(Self::output(repo, &[
+            "for-each-ref",
+            "--format=%(refname:short)",
+            format!("--merged=refs/heads/{base}").as_str(),
+            "refs/heads"
+        ]).await?
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    async fn delete_branch(&self, repo: &str, branch: &str, force: bool) -> Result<(), String> {
+        Self::run(repo, &["branch", if force { "-D" } else { "-d" }, branch]).await
+    }
+
+    async fn fast_forward(&self, repo: &str, target: &str) -> Result<(), String> {
+        Self::run(repo, &["merge", "--ff-only", "--quiet", target]).await
+    }
+
+    async fn push(&self, repo: &str, branch: &str, remote: &str, remote_branch: &str) -> Result<PushOutcome, String> {
+        // The refspec has no leading `+`, so the remote
+        // branch can only ever be fast-forwarded
+        let refspec = format!("refs/heads/{branch}:refs/heads/{remote_branch}");
+
+        let output = Self::non_interactive(repo).await
+            .args(["push", "--porcelain", remote, refspec.as_str()])
+            .output()
+            .await
+            .map_err(|e| format!("git push: {e}"))?;
+
+        if output.status.success() {
+            return Ok(PushOutcome::Pushed);
+        }
+
+        // Rejected refs are flagged with `!` and followed by the reason
+        let stdout = String::from_utf8_lossy(output.stdout.as_slice());
+        if let Some(reason) = stdout.lines().find_map(|l| l.strip_prefix('!').and_then(|l| l.split('\t').nth(2))) {
+            return Ok(PushOutcome::Rejected(reason.to_string()));
+        }
+
+        Ok(PushOutcome::Failed(String::from_utf8_lossy(output.stderr.as_slice()).trim().to_string()))
+    }
+
+    async fn grep(&self, repo: &str, pattern: &str) -> Result<Vec<GrepMatch>, String> {
+        let output = Command::new("git")
+            .args(["-C", repo, "grep", "--line-number", "--null", "-I", "--no-color", "-e", pattern])
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("git grep: {e}"))?;
+
+        // Exit code 1 means nothing matched
+        match output.status.code() {
+            Some(0) => (),
+            Some(1) => return Ok(Vec::new()),
+            _ => return Err(String::from_utf8_lossy(output.stderr.as_slice()).trim().to_string())
+        }
+
+        // Each line is formatted as `<path>\0<line>\0<text>`
+        let stdout = String::from_utf8_lossy(output.stdout.as_slice());
+        Ok(stdout
+            .lines()
+            .filter_map(|l| {
+                let mut fields = l.splitn(3, '\0');
+                Some(GrepMatch {
+                    path: fields.next()?.to_string(),
+                    line: fields.next()?.parse().ok()?,
+                    text: fields.next()?.to_string()
+                })
+            })
+            .collect())
+    }
+
+    async fn remote_url(&self, repo: &str, remote: &str) -> Result<Option<String>, String> {
+        let url = Self::output(repo, &["remote", "get-url", remote]).await?;
+
+        Ok(Some(url.trim().to_string()).filter(|u| !u.is_empty()))
+    }
+
+    async fn head_tags(&self, repo: &str) -> Result<Vec<String>, String> {
+        Ok(Self::output(repo, &["tag", "--points-at", "HEAD"]).await?
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    async fn clone(&self, url: &str, path: &str, remote: &str) -> Result<(), String> {
+        let parent = Path::new(path).parent().unwrap_or(Path::new("/"));
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+
+        let output = Self::non_interactive(".").await
+            .args(["clone", "--quiet", "--origin", remote, url, path])
+            .stdout(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("git clone: {e}"))?;
+
+        if output.status.success() {
+            Ok(())
+        }
+        else {
+            Err(String::from_utf8_lossy(output.stderr.as_slice()).trim().to_string())
+        }
+    }
+
+    async fn add_remote(&self, repo: &str, remote: &str, url: &str) -> Result<(), String> {
+        Self::run(repo, &["remote", "add", remote, url]).await
+    }
+
+    async fn set_remote_url(&self, repo: &str, remote: &str, url: &str) -> Result<(), String> {
+        Self::run(repo, &["remote", "set-url", remote, url]).await
+    }
+
+    async fn checkout(&self, repo: &str, rev: &str) -> Result<(), String> {
+        Self::run(repo, &["checkout", "--quiet", rev]).await
+    }
+
+    async fn log(&self, repo: &str, since: &str, author: Option<&str>) -> Result<Vec<LogEntry>, String> {
+        let since = format!("--since={since}");
+        let author = author.map(|a| format!("--author={a}"));
+
+        let mut args = vec![
+            "log",
+            "--branches",
+            since.as_str(),
+            "--date=format-local:%Y-%m-%d %H:%M",
+            "--format=%h%x00%ct%x00%cd%x00%an%x00%s"
+        ];
+        if let Some(author) = &author {
+            args.push(author.as_str());
+        }
+
+        let git_log_str = Self::output(repo, args.as_slice()).await?;
+
+        let mut entries = Vec::new();
+        for line in git_log_str.lines() {
+            let unexpected = || format!("git log: unexpected output '{line}'");
+
+            let fields: Vec<&str> = line.splitn(5, '\0').collect();
+            let [hash, secs, date, author, subject] = fields.as_slice()
+            else {
+                return Err(unexpected());
+            };
+            let secs: u64 = secs.parse().map_err(|_| unexpected())?;
+
+            entries.push(LogEntry {
+                hash: (*hash).to_string(),
+                time: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+                date: (*date).to_string(),
+                author: (*author).to_string(),
+                subject: (*subject).to_string()
+            });
+        }
+
+        Ok(entries)
+    }
+}
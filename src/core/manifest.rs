@@ -0,0 +1,390 @@
+//! Description of the tracked repositories that can be reproduced on another machine
+
+#![allow(clippy::missing_errors_doc)]
+
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+use clap::ValueEnum;
+
+/// Tracked repositories along with everything needed to clone them again
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Manifest {
+    #[serde(default)]
+    pub repos: Vec<ManifestRepo>
+}
+
+/// Single repository of a manifest
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct ManifestRepo {
+    /// Path relative to the home directory, absolute if it's outside of it
+    pub path: String,
+    /// Checked out branch, `None` if `HEAD` is detached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub remotes: Vec<ManifestRemote>,
+    /// Tags pointing at the checked out commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>
+}
+
+/// Remote of a manifest repository
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct ManifestRemote {
+    pub name: String,
+    pub url: String
+}
+
+/// Format of a manifest file
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ManifestFormat {
+    Toml,
+    Json,
+    Yaml
+}
+
+/// Other multi-repository tool whose list of repositories can be imported
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportSource {
+    /// Configuration file of myrepos, e.g. `~/.mrconfig`
+    Mr,
+    /// Repository list of gita, e.g. `~/.config/gita/repos.csv`
+    Gita,
+    /// Output of `ghq list`, with or without `--full-path`
+    Ghq,
+    /// Manifest XML of Google's `repo` tool
+    RepoManifest
+}
+
+impl ManifestFormat {
+    /// Guesses the format from the extension of a file, `None` if it isn't known
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "toml" => Some(ManifestFormat::Toml),
+            "json" => Some(ManifestFormat::Json),
+            "yaml" | "yml" => Some(ManifestFormat::Yaml),
+            _ => None
+        }
+    }
+}
+
+impl Manifest {
+    /// Serializes the manifest in the given format
+    pub fn to_string(&self, format: ManifestFormat) -> Result<String, String> {
+        match format {
+            ManifestFormat::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string()),
+            ManifestFormat::Json => serde_json::to_string_pretty(self)
+                .map(|s| s + "\n")
+                .map_err(|e| e.to_string()),
+            ManifestFormat::Yaml => serde_yaml_ng::to_string(self).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Parses a manifest written in the given format
+    pub fn parse(contents: &str, format: ManifestFormat) -> Result<Self, String> {
+        match format {
+            ManifestFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            ManifestFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            ManifestFormat::Yaml => serde_yaml_ng::from_str(contents).map_err(|e| e.to_string())
+        }
+    }
+}
+
+impl ManifestRepo {
+    /// Returns the remote to clone the repository from, `origin` if there is one
+    #[must_use]
+    pub fn clone_remote(&self) -> Option<&ManifestRemote> {
+        self.remotes
+            .iter()
+            .find(|r| r.name == "origin")
+            .or_else(|| self.remotes.first())
+    }
+}
+
+impl Manifest {
+    /// Translates the repository list of another tool into a manifest,
+    /// relative paths in it start at `base`: the directory of the myrepos
+    /// configuration, the ghq root or the top of the `repo` checkout,
+    /// relative fetch URLs of a `repo` manifest start at `manifest_url`,
+    /// the URL of the manifest repository
+    pub fn from_source(contents: &str, source: ImportSource, base: &Path, manifest_url: Option<&str>) -> Result<Self, String> {
+        let repos = match source {
+            ImportSource::Mr => from_mr(contents),
+            ImportSource::Gita => from_gita(contents),
+            ImportSource::Ghq => from_ghq(contents, base),
+            ImportSource::RepoManifest => from_repo_manifest(contents, manifest_url)?
+        };
+
+        // Make all paths absolute
+        Ok(Manifest {
+            repos: repos
+                .into_iter()
+                .map(|repo| ManifestRepo {
+                    path: base.join(repo.path.as_str()).to_string_lossy().into_owned(),
+                    ..repo
+                })
+                .collect()
+        })
+    }
+}
+
+// Reads the sections of a myrepos configuration, taking the URL,
+// remote name and branch from their `git clone` checkout commands
+fn from_mr(contents: &str) -> Vec<ManifestRepo> {
+    let mut repos: Vec<ManifestRepo> = Vec::new();
+    let mut section: Option<String> = None;
+
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = (name != "DEFAULT").then(|| name.to_string());
+
+            if let Some(path) = &section {
+                repos.push(ManifestRepo {
+                    path: path.clone(),
+                    ..ManifestRepo::default()
+                });
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=')
+        else {
+            continue;
+        };
+
+        if section.is_none() || key.trim() != "checkout" {
+            continue;
+        }
+
+        let words = shell_words(value);
+        let Some(clone) = words.windows(2).position(|w| w[0] == "git" && w[1] == "clone")
+        else {
+            // Not a git repository
+            repos.pop();
+            section = None;
+            continue;
+        };
+
+        let Some(repo) = repos.last_mut()
+        else {
+            continue;
+        };
+
+        let mut remote = String::from("origin");
+        let mut args = words[clone + 2..].iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-b" | "--branch" => repo.branch = args.next().cloned(),
+                "-o" | "--origin" => remote = args.next().cloned().unwrap_or(remote),
+                arg if arg.starts_with('-') => (),
+                url => {
+                    repo.remotes.push(ManifestRemote {
+                        name: remote,
+                        url: url.to_string()
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    repos
+}
+
+// Reads the paths from the first column of a gita repository list
+fn from_gita(contents: &str) -> Vec<ManifestRepo> {
+    contents
+        .lines()
+        .filter_map(|l| l.split(',').next())
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|path| ManifestRepo {
+            path: path.to_string(),
+            ..ManifestRepo::default()
+        })
+        .collect()
+}
+
+// Reads the paths listed by ghq, they mirror the repository URLs below its root
+fn from_ghq(contents: &str, root: &Path) -> Vec<ManifestRepo> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let relative = Path::new(line)
+                .strip_prefix(root)
+                .ok()
+                .and_then(|p| p.to_str())
+                .unwrap_or(line);
+
+            // Paths outside of the root don't tell where the repository comes from
+            let remotes = if Path::new(relative).is_relative() {
+                vec![ManifestRemote {
+                    name: String::from("origin"),
+                    url: format!("https://{relative}")
+                }]
+            }
+            else {
+                Vec::new()
+            };
+
+            ManifestRepo {
+                path: relative.to_string(),
+                remotes,
+                ..ManifestRepo::default()
+            }
+        })
+        .collect()
+}
+
+// Reads the projects of a `repo` manifest along with their remotes and revisions,
+// fails on fetch URLs relative to the manifest repository if its URL is unknown
+fn from_repo_manifest(contents: &str, manifest_url: Option<&str>) -> Result<Vec<ManifestRepo>, String> {
+    let document = roxmltree::Document::parse(contents).map_err(|e| e.to_string())?;
+    let elements = || document.root_element().children().filter(roxmltree::Node::is_element);
+
+    let default = elements().find(|e| e.has_tag_name("default"));
+    let default_remote = default.and_then(|d| d.attribute("remote"));
+    let default_revision = default.and_then(|d| d.attribute("revision"));
+
+    let mut repos = Vec::new();
+    for project in elements().filter(|e| e.has_tag_name("project")) {
+        let name = project.attribute("name").ok_or_else(|| String::from("project without a name"))?;
+
+        let remote = project.attribute("remote").or(default_remote);
+        let mut fetch = elements()
+            .find(|e| e.has_tag_name("remote") && e.attribute("name") == remote)
+            .and_then(|r| r.attribute("fetch"))
+            .map(String::from);
+
+        if let Some(url) = &fetch
+            && url_is_relative(url) {
+            let manifest_url = manifest_url.ok_or_else(|| format!(
+                "remote '{}' has the fetch URL '{url}' relative to the manifest repository, whose URL is unknown",
+                remote.unwrap_or_default()
+            ))?;
+            fetch = Some(resolve_url(url, manifest_url));
+        }
+
+        // Fetch URLs of scp-like hosts may end right after the host
+        let remotes = match (remote, fetch) {
+            (Some(remote), Some(fetch)) if fetch.ends_with(':') => vec![ManifestRemote {
+                name: remote.to_string(),
+                url: format!("{fetch}{name}")
+            }],
+            (Some(remote), Some(fetch)) => vec![ManifestRemote {
+                name: remote.to_string(),
+                url: format!("{}/{name}", fetch.trim_end_matches('/'))
+            }],
+            _ => Vec::new()
+        };
+
+        // Revisions are branches, tags or commits
+        let revision = project.attribute("revision").or(default_revision);
+        let (branch, tags) = match revision {
+            Some(rev) => {
+                if let Some(tag) = rev.strip_prefix("refs/tags/") {
+                    (None, vec![tag.to_string()])
+                }
+                else if rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()) {
+                    (None, Vec::new())
+                }
+                else {
+                    (Some(rev.strip_prefix("refs/heads/").unwrap_or(rev).to_string()), Vec::new())
+                }
+            },
+            None => (None, Vec::new())
+        };
+
+        repos.push(ManifestRepo {
+            path: project.attribute("path").unwrap_or(name).to_string(),
+            branch,
+            remotes,
+            tags
+        });
+    }
+
+    Ok(repos)
+}
+
+// Checks if a URL lacks a scheme, an scp-like host and an absolute path
+fn url_is_relative(url: &str) -> bool {
+    !url.contains("://")
+        && !url.starts_with('/')
+        && !url.split('/').next().is_some_and(|s| s.contains(':'))
+}
+
+// Resolves a relative URL against the URL of a repository the same way `repo` does,
+// e.g. `..` next to `https://example.com/platform/manifest` becomes `https://example.com/`
+fn resolve_url(url: &str, base: &str) -> String {
+    let base = base.trim_end_matches('/');
+
+    // Only the path after the scheme and host can be walked up
+    let root_len = match base.find("://") {
+        Some(i) => base[i + 3..].find('/').map_or(base.len(), |j| i + 3 + j + 1),
+        None => match base.split('/').next().and_then(|s| s.find(':')) {
+            Some(i) => i + 1,
+            None => usize::from(base.starts_with('/'))
+        }
+    };
+    let (root, path) = base.split_at(root_len);
+
+    // The last segment names the repository itself
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    segments.pop();
+
+    for segment in url.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            },
+            segment => segments.push(segment)
+        }
+    }
+
+    if root.contains("://") && !root.ends_with('/') {
+        format!("{root}/{}", segments.join("/"))
+    }
+    else {
+        format!("{root}{}", segments.join("/"))
+    }
+}
+
+// Splits a shell command into words, honoring single and double quotes
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    words
+}
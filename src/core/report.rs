@@ -0,0 +1,1144 @@
+//! Details collected by the `check` command and their presentation
+
+#![allow(clippy::missing_errors_doc)]
+
+use crate::core::git::{FetchOutcome, PushOutcome, GrepMatch, LogEntry, FileStatus, RepoState, Head, Operation};
+use crate::utils::{APP_NAME, format_age};
+
+use std::fmt;
+use std::cmp::Reverse;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use clap::ValueEnum;
+use colored::Colorize;
+use console::{measure_text_width, pad_str, Alignment};
+
+/// Everything found out about a single repository
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct RepoReport {
+    /// Absolute path of the repository
+    pub path: String,
+    /// Changes in the working tree
+    pub status: StatusReport,
+    /// State of `HEAD`, unfinished operations and stashes
+    pub state: Option<RepoState>,
+    /// Comparison of each local branch with its remote counterparts
+    pub branches: Vec<BranchReport>,
+    /// Fetch results of each remote
+    pub remotes: Vec<RemoteReport>,
+    /// Committer date of the last commit on `HEAD` in seconds since the
+    /// Unix epoch, `None` if there are no commits yet
+    pub last_commit: Option<u64>,
+    /// Author of the last commit on `HEAD`
+    pub last_commit_author: Option<String>,
+    /// Latest modification time of the index and the changed or
+    /// untracked files in seconds since the Unix epoch
+    pub last_modified: Option<u64>,
+    /// Time of the last fetch in seconds since the Unix epoch, `None` if there was none
+    pub last_fetch: Option<u64>,
+    /// Local branches that can likely be deleted, filled only on request
+    pub stale_branches: Vec<StaleBranch>,
+    /// Reason the inspection failed, the rest of the report is empty if set
+    pub error: Option<String>
+}
+
+/// Changes in the working tree, summarized and file by file
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct StatusReport {
+    pub summary: StatusSummary,
+    pub files: Vec<FileStatus>
+}
+
+/// Number of files in each category of changes, a file
+/// can fall into more than one, e.g. staged and modified
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct StatusSummary {
+    pub staged: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    pub ignored_large: usize
+}
+
+/// Comparison of a local branch with its remote counterparts
+#[derive(Serialize, Clone, Debug)]
+pub struct BranchReport {
+    pub name: String,
+    /// Comparison with the configured upstream, `None` if there is no upstream
+    pub upstream: Option<Divergence>,
+    /// Comparison with the push destination, only if it differs from the upstream
+    pub push: Option<Divergence>,
+    /// Comparison with the same-named branch on every remote,
+    /// filled only when all remotes are inspected
+    pub remotes: Vec<Divergence>
+}
+
+/// Difference between a local branch and a remote branch
+#[derive(Serialize, Clone, Debug)]
+pub struct Divergence {
+    pub remote: String,
+    pub remote_branch: String,
+    /// `None` if the remote branch doesn't exist
+    pub counts: Option<AheadBehind>
+}
+
+/// Number of commits the local branch is ahead of and behind the remote one
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AheadBehind {
+    pub ahead: u32,
+    pub behind: u32
+}
+
+/// Fetch result of a single remote
+#[derive(Serialize, Clone, Debug)]
+pub struct RemoteReport {
+    pub name: String,
+    pub fetch: FetchOutcome
+}
+
+/// Kind of finding a repository can be reported for
+#[derive(Serialize, ValueEnum, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Condition {
+    /// Uncommitted changes in the working tree
+    Dirty,
+    /// Stashed changes
+    Stashed,
+    /// Unfinished rebase, merge, cherry-pick, revert, bisect or am
+    InProgress,
+    /// Detached HEAD
+    Detached,
+    /// Commits not pushed to the upstream or push destination
+    Unpushed,
+    /// Commits not pulled from the upstream
+    Behind,
+    /// No remotes configured
+    NoRemote,
+    /// Branches without an upstream or with one that's gone
+    MissingUpstream,
+    /// Remotes that couldn't be fetched
+    FetchFailed,
+    /// Local branches merged into the default branch, with a gone
+    /// upstream or without recent commits (only with --branches)
+    StaleBranch
+}
+
+/// Local branch that can likely be deleted
+#[derive(Serialize, Clone, Debug)]
+pub struct StaleBranch {
+    pub name: String,
+    pub reasons: Vec<StaleReason>
+}
+
+/// Reason a local branch is considered stale
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum StaleReason {
+    /// Fully merged into the default branch
+    Merged { into: String },
+    /// The configured upstream no longer exists
+    Gone { upstream: String },
+    /// No commits for the given number of seconds
+    Inactive { age: u64 }
+}
+
+/// Order of the repositories in the output of `check`
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortKey {
+    /// Alphabetically by path
+    Path,
+    /// Most changed files first
+    Dirty,
+    /// Most unpushed commits first
+    Ahead,
+    /// Most commits to pull first
+    Behind,
+    /// Most recently committed to first
+    LastCommit
+}
+
+/// Result of fast-forwarding the current branch of a repository to its upstream
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SyncOutcome {
+    /// The branch has been fast-forwarded by the given number of commits
+    FastForwarded { branch: String, commits: u32 },
+    /// The branch would be fast-forwarded by the given number of commits
+    WouldFastForward { branch: String, commits: u32 },
+    /// There is nothing to pull
+    UpToDate,
+    /// The repository is in a state not safe to update, for the given reason
+    Skipped(String),
+    /// The repository couldn't be inspected or updated
+    Failed(String)
+}
+
+/// Local branch with commits missing from the branch it gets pushed to
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnpushedBranch {
+    pub name: String,
+    pub remote: String,
+    /// Short name of the remote branch, e.g. `origin/main`
+    pub remote_branch: String,
+    pub ahead: u32,
+    /// Commits only the remote branch has, pushing over them would take a force push
+    pub behind: u32
+}
+
+/// Result of importing a single repository of a manifest
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ImportOutcome {
+    /// The repository has been cloned
+    Cloned,
+    /// The repository already existed, with the given differences from
+    /// the manifest, its remotes have been brought in line with it
+    Existing(Vec<String>),
+    /// The repository is missing and can't be cloned for the given reason
+    Skipped(String),
+    /// The repository couldn't be cloned or updated
+    Failed(String)
+}
+
+/// Lines of a repository matching the `grep` pattern
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct GrepReport {
+    pub path: String,
+    pub matches: Vec<GrepMatch>,
+    /// Why the repository couldn't be searched
+    pub error: Option<String>
+}
+
+/// Exit status of `check --exit-code` if a repository has uncommitted changes
+pub const EXIT_DIRTY: i32 = 8;
+/// Exit status of `check --exit-code` if a branch has unpushed commits
+pub const EXIT_UNPUSHED: i32 = 16;
+/// Exit status of `check --exit-code` if a branch is behind its remote
+pub const EXIT_BEHIND: i32 = 32;
+/// Exit status of `check --exit-code` if a repository couldn't be inspected or fetched
+pub const EXIT_ERRORS: i32 = 64;
+
+/// Outcome of inspecting all the requested repositories
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct CheckSummary {
+    /// Selected conditions met by any of the repositories
+    pub conditions: Vec<Condition>,
+    /// Number of repositories that couldn't be inspected
+    pub errors: usize
+}
+
+impl Condition {
+    /// Conditions concerning the working tree
+    pub const LOCAL: [Condition; 4] = [
+        Condition::Dirty,
+        Condition::Stashed,
+        Condition::InProgress,
+        Condition::Detached
+    ];
+
+    /// Conditions concerning the remotes
+    pub const REMOTE: [Condition; 5] = [
+        Condition::Unpushed,
+        Condition::Behind,
+        Condition::NoRemote,
+        Condition::MissingUpstream,
+        Condition::FetchFailed
+    ];
+
+    /// Checks if the condition is among the selected ones,
+    /// an empty selection selects all of them
+    #[must_use]
+    pub fn is_selected(self, only: &[Condition]) -> bool {
+        only.is_empty() || only.contains(&self)
+    }
+}
+
+impl CheckSummary {
+    /// Returns the exit status of `check --exit-code`, the codes of
+    /// all the encountered problems are combined, 0 means there are none
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        let mut code = 0;
+
+        for condition in &self.conditions {
+            code |= match condition {
+                Condition::Dirty => EXIT_DIRTY,
+                Condition::Unpushed => EXIT_UNPUSHED,
+                Condition::Behind => EXIT_BEHIND,
+                Condition::FetchFailed => EXIT_ERRORS,
+                _ => 0
+            };
+        }
+
+        if self.errors > 0 {
+            code |= EXIT_ERRORS;
+        }
+
+        code
+    }
+}
+
+impl RepoReport {
+    /// Returns a report of a repository that couldn't be inspected
+    #[must_use]
+    pub fn failed(path: &str, error: String) -> Self {
+        Self {
+            path: path.to_string(),
+            error: Some(error),
+            ..Self::default()
+        }
+    }
+
+    /// Returns every condition the repository meets,
+    /// none if it couldn't be inspected
+    #[must_use]
+    pub fn conditions(&self) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+
+        if self.error.is_some() {
+            return conditions;
+        }
+
+        if self.status.has_findings() {
+            conditions.push(Condition::Dirty);
+        }
+
+        if let Some(state) = &self.state {
+            conditions.extend(state.conditions());
+        }
+
+        if self.remotes.is_empty() {
+            conditions.push(Condition::NoRemote);
+        }
+        else {
+            // Branches of repositories without remotes have nothing to be compared with
+            conditions.extend(self.branches.iter().flat_map(BranchReport::conditions));
+        }
+
+        if self.remotes.iter().any(RemoteReport::fetch_failed) {
+            conditions.push(Condition::FetchFailed);
+        }
+
+        if !self.stale_branches.is_empty() {
+            conditions.push(Condition::StaleBranch);
+        }
+
+        conditions.sort_unstable();
+        conditions.dedup();
+        conditions
+    }
+
+    /// Checks if the repository meets any of the selected conditions
+    #[must_use]
+    pub fn has_findings(&self, only: &[Condition]) -> bool {
+        self.conditions().into_iter().any(|c| c.is_selected(only))
+    }
+
+    /// Returns the number of commits all branches are ahead of their upstream
+    #[must_use]
+    pub fn ahead(&self) -> u32 {
+        self.upstream_counts().map(|c| c.ahead).sum()
+    }
+
+    /// Returns the number of commits all branches are behind their upstream
+    #[must_use]
+    pub fn behind(&self) -> u32 {
+        self.upstream_counts().map(|c| c.behind).sum()
+    }
+
+    // Returns the commit counts of the branches with an existing upstream
+    fn upstream_counts(&self) -> impl Iterator<Item = &AheadBehind> {
+        self.branches
+            .iter()
+            .filter_map(|b| b.upstream.as_ref().and_then(|u| u.counts.as_ref()))
+    }
+
+    /// Returns the current branch, its upstream and the number of commits
+    /// the branch can be fast-forwarded by, or why it can't be fast-forwarded
+    pub fn fast_forward_target(&self) -> Result<(&str, &str, u32), SyncOutcome> {
+        if let Some(e) = &self.error {
+            return Err(SyncOutcome::Failed(e.clone()));
+        }
+
+        let state = self.state.as_ref().ok_or_else(|| SyncOutcome::Skipped(String::from("state unknown")))?;
+        if let Some(operation) = state.operation {
+            return Err(SyncOutcome::Skipped(format!("{operation} in progress")));
+        }
+
+        let Head::Branch(current) = &state.head
+        else {
+            return Err(SyncOutcome::Skipped(String::from("HEAD is detached")));
+        };
+
+        if self.status.has_findings() {
+            return Err(SyncOutcome::Skipped(String::from("working tree has uncommitted changes")));
+        }
+
+        let upstream = self.branches
+            .iter()
+            .find(|b| &b.name == current)
+            .and_then(|b| b.upstream.as_ref())
+            .ok_or_else(|| SyncOutcome::Skipped(String::from("no upstream configured")))?;
+
+        match upstream.counts {
+            None => Err(SyncOutcome::Skipped(format!("upstream {} is gone", upstream.remote_branch))),
+            Some(AheadBehind { behind: 0, .. }) => Err(SyncOutcome::UpToDate),
+            Some(AheadBehind { ahead: 0, behind }) => Ok((current.as_str(), upstream.remote_branch.as_str(), behind)),
+            Some(AheadBehind { ahead, behind }) => Err(SyncOutcome::Skipped(format!(
+                "{current} has diverged from {} ({ahead} ahead, {behind} behind)",
+                upstream.remote_branch
+            )))
+        }
+    }
+
+    /// Returns the branches ahead of their push destination, or their upstream
+    /// if they don't have a separate one, skips local upstreams
+    #[must_use]
+    pub fn unpushed_branches(&self) -> Vec<UnpushedBranch> {
+        self.branches
+            .iter()
+            .filter_map(|b| {
+                let destination = b.push.as_ref().or(b.upstream.as_ref())?;
+                let counts = destination.counts?;
+
+                (counts.ahead > 0 && destination.remote != ".").then(|| UnpushedBranch {
+                    name: b.name.clone(),
+                    remote: destination.remote.clone(),
+                    remote_branch: destination.remote_branch.clone(),
+                    ahead: counts.ahead,
+                    behind: counts.behind
+                })
+            })
+            .collect()
+    }
+
+    /// Returns a displayable list of findings meeting the selected conditions,
+    /// its alternate form (`{:#}`) lists every changed file
+    #[must_use]
+    pub fn findings<'a>(&'a self, only: &'a [Condition]) -> Findings<'a> {
+        Findings {
+            report: self,
+            only
+        }
+    }
+
+    /// Returns the time of the latest commit, modification or fetch
+    /// in seconds since the Unix epoch, whichever came last
+    #[must_use]
+    pub fn last_activity(&self) -> Option<u64> {
+        [self.last_commit, self.last_modified, self.last_fetch]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+
+    // Checks if none of the remotes has been fetched during the inspection
+    fn fetch_skipped(&self) -> bool {
+        self.remotes.iter().all(|r| r.fetch == FetchOutcome::Skipped)
+    }
+}
+
+impl StatusReport {
+    /// Summarizes the given `git status` entries
+    #[must_use]
+    pub fn new(files: Vec<FileStatus>) -> Self {
+        let mut summary = StatusSummary::default();
+
+        for file in &files {
+            let mut code = file.code.chars();
+            let (index, worktree) = (code.next().unwrap_or(' '), code.next().unwrap_or(' '));
+
+            match (index, worktree) {
+                _ if file.conflicted => summary.conflicted += 1,
+                ('?', '?') => summary.untracked += 1,
+                ('!', '!') => summary.ignored_large += 1,
+                _ => {
+                    if index != ' ' {
+                        summary.staged += 1;
+                    }
+                    if matches!(worktree, 'M' | 'T') {
+                        summary.modified += 1;
+                    }
+                    if index == 'D' || worktree == 'D' {
+                        summary.deleted += 1;
+                    }
+                    if file.renamed_from.is_some() {
+                        summary.renamed += 1;
+                    }
+                }
+            }
+        }
+
+        Self {
+            summary,
+            files
+        }
+    }
+
+    /// Returns the number of changed files, large ignored files don't count
+    #[must_use]
+    pub fn changes(&self) -> usize {
+        self.files.iter().filter(|f| f.code != "!!").count()
+    }
+
+    /// Checks if there are any changes, large ignored files alone don't count
+    #[must_use]
+    pub fn has_findings(&self) -> bool {
+        self.summary != StatusSummary {
+            ignored_large: self.summary.ignored_large,
+            ..StatusSummary::default()
+        }
+    }
+}
+
+impl RepoState {
+    /// Returns the unfinished operation, detached `HEAD` and stashes as conditions
+    #[must_use]
+    pub fn conditions(&self) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+
+        if self.operation.is_some() {
+            conditions.push(Condition::InProgress);
+        }
+        if matches!(self.head, Head::Detached(_)) {
+            conditions.push(Condition::Detached);
+        }
+        if self.stashes > 0 {
+            conditions.push(Condition::Stashed);
+        }
+
+        conditions
+    }
+
+    // Writes the findings meeting the selected conditions
+    fn write_findings(&self, f: &mut fmt::Formatter<'_>, only: &[Condition]) -> fmt::Result {
+        if let Some(operation) = self.operation
+            && Condition::InProgress.is_selected(only) {
+            writeln!(f, "  {}", format!("{operation} in progress").red())?;
+        }
+
+        if let Head::Detached(commit) = &self.head
+            && Condition::Detached.is_selected(only) {
+            writeln!(f, "  {}", format!("HEAD detached at {commit}").yellow())?;
+        }
+
+        if self.stashes > 0 && Condition::Stashed.is_selected(only) {
+            writeln!(f, "  {}", format!("{} stash(es)", self.stashes).yellow())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BranchReport {
+    /// Returns the conditions of the branch, a missing push
+    /// destination means the branch hasn't been pushed yet
+    #[must_use]
+    pub fn conditions(&self) -> Vec<Condition> {
+        let mut conditions = match &self.upstream {
+            Some(upstream) => upstream.conditions(),
+            None => vec![Condition::MissingUpstream]
+        };
+
+        if let Some(push) = &self.push {
+            conditions.extend(push.conditions().into_iter().map(|c| match c {
+                Condition::MissingUpstream => Condition::Unpushed,
+                c => c
+            }));
+        }
+
+        for divergence in &self.remotes {
+            conditions.extend(divergence.conditions());
+        }
+
+        conditions
+    }
+
+    /// Checks if the branch meets any of the selected conditions
+    #[must_use]
+    pub fn has_findings(&self, only: &[Condition]) -> bool {
+        self.conditions().into_iter().any(|c| c.is_selected(only))
+    }
+
+    // Writes the comparisons meeting the selected conditions
+    fn write_findings(&self, f: &mut fmt::Formatter<'_>, only: &[Condition]) -> fmt::Result {
+        writeln!(f, "  {}:", self.name.underline())?;
+
+        let selected = |d: &Divergence| d.conditions().into_iter().any(|c| c.is_selected(only));
+
+        // Show the matrix of all remotes if it has been requested,
+        // the upstream and push destination otherwise
+        if !self.remotes.is_empty() {
+            for divergence in self.remotes.iter().filter(|d| selected(d)) {
+                writeln!(f, "    {divergence}")?;
+            }
+
+            return Ok(());
+        }
+
+        match &self.upstream {
+            Some(upstream) if !selected(upstream) => (),
+            Some(upstream) if upstream.counts.is_none() => writeln!(f, "    upstream {} is gone", upstream.remote_branch)?,
+            Some(upstream) => writeln!(f, "    {upstream}")?,
+            None if Condition::MissingUpstream.is_selected(only) => writeln!(f, "    no upstream configured")?,
+            None => ()
+        }
+
+        if let Some(push) = &self.push {
+            let push_selected = push.conditions().into_iter().any(|c| match c {
+                Condition::MissingUpstream => Condition::Unpushed.is_selected(only),
+                c => c.is_selected(only)
+            });
+
+            if push_selected {
+                writeln!(f, "    {push} (push)")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Divergence {
+    /// Returns `MissingUpstream` if the remote branch doesn't exist,
+    /// `Unpushed` and `Behind` if the local branch is ahead of or behind it
+    #[must_use]
+    pub fn conditions(&self) -> Vec<Condition> {
+        match self.counts {
+            None => vec![Condition::MissingUpstream],
+            Some(AheadBehind { ahead, behind }) => {
+                let mut conditions = Vec::new();
+                if ahead > 0 {
+                    conditions.push(Condition::Unpushed);
+                }
+                if behind > 0 {
+                    conditions.push(Condition::Behind);
+                }
+                conditions
+            }
+        }
+    }
+}
+
+impl RemoteReport {
+    /// Checks if fetching the remote went wrong
+    #[must_use]
+    pub fn fetch_failed(&self) -> bool {
+        matches!(self.fetch, FetchOutcome::TimedOut | FetchOutcome::AuthFailed(_) | FetchOutcome::Failed(_))
+    }
+}
+
+/// Findings of a repository meeting the selected conditions, see `RepoReport::findings`
+pub struct Findings<'a> {
+    report: &'a RepoReport,
+    only: &'a [Condition]
+}
+
+impl fmt::Display for Findings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (report, only) = (self.report, self.only);
+
+        writeln!(f, "{}", report.path.bold())?;
+
+        // Tell when the repository has been worked on in the alternate form
+        if f.alternate() {
+            let mut activity = Vec::new();
+            if let Some(commit) = report.last_commit {
+                let author = report.last_commit_author.as_deref().unwrap_or_default();
+                activity.push(format!("last commit {} ago by {author}", age_of(commit)));
+            }
+            if let Some(modified) = report.last_modified {
+                activity.push(format!("modified {} ago", age_of(modified)));
+            }
+            if let Some(fetch) = report.last_fetch {
+                activity.push(format!("fetched {} ago", age_of(fetch)));
+            }
+
+            if !activity.is_empty() {
+                writeln!(f, "  {}", activity.join(", ").dimmed())?;
+            }
+        }
+
+        // Pass the alternate form on to list every changed file
+        if Condition::Dirty.is_selected(only) {
+            if f.alternate() {
+                write!(f, "{:#}", report.status)?;
+            }
+            else {
+                write!(f, "{}", report.status)?;
+            }
+        }
+
+        if let Some(state) = &report.state {
+            state.write_findings(f, only)?;
+        }
+
+        if !report.stale_branches.is_empty() && Condition::StaleBranch.is_selected(only) {
+            writeln!(f, "  {}", "stale branches:".underline())?;
+            for branch in &report.stale_branches {
+                writeln!(f, "    {branch}")?;
+            }
+        }
+
+        if report.remotes.is_empty() {
+            if Condition::NoRemote.is_selected(only) {
+                writeln!(f, "  {}", "no remotes configured".yellow())?;
+            }
+
+            return Ok(());
+        }
+
+        let mut branches = report.branches.iter().filter(|b| b.has_findings(only)).peekable();
+        let branches_shown = branches.peek().is_some();
+
+        for branch in branches {
+            branch.write_findings(f, only)?;
+        }
+
+        if Condition::FetchFailed.is_selected(only) {
+            for remote in &report.remotes {
+                write!(f, "{remote}")?;
+            }
+        }
+
+        // Tell how old the compared remote data is if it wasn't just fetched
+        if report.fetch_skipped() && branches_shown {
+            match report.last_fetch {
+                Some(fetch) => writeln!(f, "  {}", format!("remote data from {} ago", age_of(fetch)).dimmed()),
+                None => writeln!(f, "  {}", "remote data never fetched".dimmed())
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+// Shows all findings, the alternate form lists every changed file
+impl fmt::Display for RepoReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self.findings(&[]))
+        }
+        else {
+            write!(f, "{}", self.findings(&[]))
+        }
+    }
+}
+
+// Lists every file in the alternate form (`{:#}`), prints a one-line summary otherwise
+impl fmt::Display for StatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            for file in &self.files {
+                match &file.renamed_from {
+                    Some(from) => writeln!(f, "  {} {from} -> {}", file.code, file.path)?,
+                    None => writeln!(f, "  {} {}", file.code, file.path)?
+                }
+            }
+
+            return Ok(());
+        }
+
+        let counts = [
+            (self.summary.conflicted, "conflicted"),
+            (self.summary.staged, "staged"),
+            (self.summary.modified, "modified"),
+            (self.summary.deleted, "deleted"),
+            (self.summary.renamed, "renamed"),
+            (self.summary.untracked, "untracked"),
+            (self.summary.ignored_large, "ignored but large")
+        ];
+
+        let summary: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, category)| format!("{count} {category}"))
+            .collect();
+
+        if !summary.is_empty() {
+            writeln!(f, "  {}", summary.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Rebase => write!(f, "rebase"),
+            Operation::Am => write!(f, "am"),
+            Operation::Merge => write!(f, "merge"),
+            Operation::CherryPick => write!(f, "cherry-pick"),
+            Operation::Revert => write!(f, "revert"),
+            Operation::Bisect => write!(f, "bisect")
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let remote_branch = self.remote_branch.as_str();
+
+        match self.counts {
+            None => write!(f, "missing from '{}' remote", self.remote),
+            Some(AheadBehind { ahead: 0, behind: 0 }) => write!(f, "up to date with {remote_branch}"),
+            Some(AheadBehind { ahead: 0, behind }) => write!(f, "{behind} commit(s) behind {remote_branch}"),
+            Some(AheadBehind { ahead, behind: 0 }) => write!(f, "{ahead} commit(s) ahead of {remote_branch}"),
+            Some(AheadBehind { ahead, behind }) => write!(f, "{ahead} commit(s) ahead of, {behind} commit(s) behind {remote_branch}")
+        }
+    }
+}
+
+impl fmt::Display for RemoteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.fetch {
+            FetchOutcome::TimedOut => writeln!(f, "  {}", format!("fetch from '{}' timed out", self.name).yellow()),
+            FetchOutcome::AuthFailed(e) => {
+                // Only the first line of the error message fits in here
+                let e = e.lines().next().unwrap_or_default();
+                writeln!(f, "  {}", format!("authentication to '{}' failed: {e}", self.name).red())
+            },
+            FetchOutcome::Failed(e) => {
+                let e = e.lines().next().unwrap_or_default();
+                writeln!(f, "  {}", format!("fetch from '{}' failed: {e}", self.name).yellow())
+            },
+            FetchOutcome::Fetched | FetchOutcome::Skipped => Ok(())
+        }
+    }
+}
+
+impl StaleBranch {
+    /// Checks if the branch has been merged, so it can be deleted without losing any commits
+    #[must_use]
+    pub fn is_merged(&self) -> bool {
+        self.reasons.iter().any(|r| matches!(r, StaleReason::Merged { .. }))
+    }
+}
+
+impl fmt::Display for SyncOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncOutcome::FastForwarded { branch, commits } => write!(f, "{}", format!("fast-forwarded {branch} by {commits} commit(s)").green()),
+            SyncOutcome::WouldFastForward { branch, commits } => write!(f, "would fast-forward {branch} by {commits} commit(s)"),
+            SyncOutcome::UpToDate => write!(f, "up to date"),
+            SyncOutcome::Skipped(reason) => write!(f, "{}", format!("skipped, {reason}").yellow()),
+            SyncOutcome::Failed(e) => {
+                // Only the first line of the error message fits in here
+                let e = e.lines().next().unwrap_or_default();
+                write!(f, "{}", format!("failed: {e}").red())
+            }
+        }
+    }
+}
+
+impl GrepReport {
+    /// Returns the files with matching lines along
+    /// with their number, in the order `git grep` lists them
+    #[must_use]
+    pub fn files(&self) -> Vec<(&str, usize)> {
+        let mut files: Vec<(&str, usize)> = Vec::new();
+
+        for m in &self.matches {
+            match files.last_mut() {
+                Some((path, count)) if *path == m.path => *count += 1,
+                _ => files.push((m.path.as_str(), 1))
+            }
+        }
+
+        files
+    }
+}
+
+impl UnpushedBranch {
+    /// Checks if the remote branch has commits of its own, so pushing would be rejected
+    #[must_use]
+    pub fn has_diverged(&self) -> bool {
+        self.behind > 0
+    }
+
+    /// Returns the name of the branch on the remote, e.g. `main` for `origin/main`
+    #[must_use]
+    pub fn remote_ref(&self) -> &str {
+        self.remote_branch
+            .strip_prefix(self.remote.as_str())
+            .and_then(|b| b.strip_prefix('/'))
+            .unwrap_or(self.remote_branch.as_str())
+    }
+}
+
+impl fmt::Display for UnpushedBranch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} commit(s) ahead of {}", self.name, self.ahead, self.remote_branch)?;
+
+        if self.has_diverged() {
+            write!(f, " {}", format!("(diverged, {} commit(s) behind, needs a force push)", self.behind).yellow())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for PushOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushOutcome::Pushed => write!(f, "{}", "pushed".green()),
+            PushOutcome::Rejected(reason) => write!(f, "{}", format!("rejected: {reason}").red()),
+            PushOutcome::Failed(e) => {
+                // Only the first line of the error message fits in here
+                let e = e.lines().next().unwrap_or_default();
+                write!(f, "{}", format!("failed: {e}").red())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ImportOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportOutcome::Cloned => write!(f, "{}", "cloned".green()),
+            ImportOutcome::Existing(changes) if changes.is_empty() => write!(f, "already present"),
+            ImportOutcome::Existing(changes) => write!(f, "already present, {}", changes.join(", ").yellow()),
+            ImportOutcome::Skipped(reason) => write!(f, "{}", format!("skipped, {reason}").yellow()),
+            ImportOutcome::Failed(e) => {
+                // Only the first line of the error message fits in here
+                let e = e.lines().next().unwrap_or_default();
+                write!(f, "{}", format!("failed: {e}").red())
+            }
+        }
+    }
+}
+
+impl fmt::Display for StaleBranch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reasons: Vec<String> = self.reasons.iter().map(ToString::to_string).collect();
+        write!(f, "{}: {}", self.name, reasons.join(", "))
+    }
+}
+
+impl fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaleReason::Merged { into } => write!(f, "merged into {into}"),
+            StaleReason::Gone { upstream } => write!(f, "upstream {upstream} is gone"),
+            StaleReason::Inactive { age } => write!(f, "no commits for {}", format_age(Duration::from_secs(*age)))
+        }
+    }
+}
+
+// Formats the time elapsed since a given number of seconds since the Unix epoch
+fn age_of(timestamp: u64) -> String {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp);
+    format_age(SystemTime::now().duration_since(time).unwrap_or_default())
+}
+
+/// Sorts the reports by the given key, reports with equal
+/// keys stay in the order they have been given in
+pub fn sort(reports: &mut [RepoReport], key: SortKey) {
+    match key {
+        SortKey::Path => reports.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::Dirty => reports.sort_by_key(|r| Reverse(r.status.changes())),
+        SortKey::Ahead => reports.sort_by_key(|r| Reverse(r.ahead())),
+        SortKey::Behind => reports.sort_by_key(|r| Reverse(r.behind())),
+        SortKey::LastCommit => reports.sort_by_key(|r| Reverse(r.last_commit))
+    }
+}
+
+/// Lists the repositories along with the time elapsed since their last activity
+#[must_use]
+pub fn activity(reports: &[RepoReport]) -> String {
+    let width = reports.iter().map(|r| measure_text_width(r.path.as_str())).max().unwrap_or_default();
+
+    let lines: Vec<String> = reports
+        .iter()
+        .map(|r| {
+            let activity = r.last_activity().map_or_else(|| String::from("never active"), |t| format!("active {} ago", age_of(t)));
+            format!("{}  {}\n", pad_str(r.path.as_str(), width, Alignment::Left, None).bold(), activity.dimmed())
+        })
+        .collect();
+
+    lines.concat()
+}
+
+/// Merges the commits of all repositories into one stream, newest first,
+/// with the repository path shown on each line
+#[must_use]
+pub fn log(logs: &[(String, Vec<LogEntry>)]) -> String {
+    let mut entries: Vec<(&str, &LogEntry)> = logs
+        .iter()
+        .flat_map(|(repo, entries)| entries.iter().map(move |e| (repo.as_str(), e)))
+        .collect();
+    entries.sort_by_key(|(_, e)| Reverse(e.time));
+
+    let width = entries.iter().map(|(repo, _)| measure_text_width(repo)).max().unwrap_or_default();
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|(repo, e)| format!(
+            "{}  {}  {} {} {}\n",
+            e.date.dimmed(),
+            pad_str(repo, width, Alignment::Left, None).bold(),
+            e.hash.yellow(),
+            e.subject,
+            format!("({})", e.author).dimmed()
+        ))
+        .collect();
+
+    lines.concat()
+}
+
+/// Lists the repositories that couldn't be inspected along with the reasons,
+/// returns an empty string if all of them have been
+#[must_use]
+pub fn errors(reports: &[RepoReport]) -> String {
+    let lines: Vec<String> = reports
+        .iter()
+        .filter_map(|r| r.error.as_ref().map(|e| format!("  {}: {e}\n", r.path.bold())))
+        .collect();
+
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    format!("{APP_NAME}: Could not inspect the following repositories:\n{}", lines.concat())
+}
+
+/// Renders the reports as a table with one row per repository, shortens
+/// the repository paths if the table would be wider than `max_width`
+#[must_use]
+pub fn table(reports: &[RepoReport], max_width: Option<usize>) -> String {
+    let header = ["REPOSITORY", "BRANCH", "CHANGES", "UPSTREAM", "STASHES", "FETCHED"];
+
+    let rows: Vec<[String; 6]> = reports
+        .iter()
+        .map(|r| [
+            r.path.clone(),
+            r.table_branch(),
+            r.table_changes(),
+            r.table_upstream(),
+            r.state.as_ref().map_or_else(|| String::from("-"), |s| s.stashes.to_string()),
+            r.table_fetched()
+        ])
+        .collect();
+
+    // Width of each column is the width of its widest cell
+    let mut widths = header.map(measure_text_width);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(measure_text_width(cell));
+        }
+    }
+
+    // Shrink the repository column to fit the table in the given width,
+    // the column separators take two characters each
+    if let Some(max_width) = max_width {
+        let total: usize = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+        if total > max_width {
+            widths[0] = widths[0].saturating_sub(total - max_width).max(header[0].len());
+        }
+    }
+
+    let mut output = String::new();
+
+    let header_line: Vec<String> = header
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| pad_str(cell, width, Alignment::Left, None).to_string())
+        .collect();
+    output.push_str(format!("{}\n", header_line.join("  ").trim_end().bold()).as_str());
+
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                // Cut the beginning of the paths, their ends tell more
+                if i == 0 && measure_text_width(cell) > width {
+                    truncate_start(cell, width)
+                }
+                else {
+                    pad_str(cell, width, Alignment::Left, None).to_string()
+                }
+            })
+            .collect();
+        output.push_str(format!("{}\n", line.join("  ").trim_end()).as_str());
+    }
+
+    output
+}
+
+// Replaces the beginning of a string with an ellipsis to fit it in the given width
+fn truncate_start(s: &str, width: usize) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(width.saturating_sub(1))..].iter().collect();
+
+    format!("…{tail}")
+}
+
+// Helpers producing the cells of the `table` function
+impl RepoReport {
+    // Checked out branch or commit
+    fn table_branch(&self) -> String {
+        match self.state.as_ref().map(|s| &s.head) {
+            Some(Head::Branch(branch)) => branch.clone(),
+            Some(Head::Detached(commit)) => format!("({commit})"),
+            None => String::from("-")
+        }
+    }
+
+    // Non-zero counts of changes marked with letters
+    fn table_changes(&self) -> String {
+        let summary = self.status.summary;
+        let counts = [
+            (summary.conflicted, 'U'),
+            (summary.staged, 'S'),
+            (summary.modified, 'M'),
+            (summary.deleted, 'D'),
+            (summary.renamed, 'R'),
+            (summary.untracked, '?')
+        ];
+
+        let cell: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, letter)| format!("{count}{letter}"))
+            .collect();
+
+        if cell.is_empty() { String::from("-") } else { cell.join(" ") }
+    }
+
+    // Commits ahead of and behind the upstream of the checked out branch
+    fn table_upstream(&self) -> String {
+        let Some(Head::Branch(head)) = self.state.as_ref().map(|s| &s.head) else {
+            return String::from("-");
+        };
+
+        match self.branches.iter().find(|b| &b.name == head).map(|b| &b.upstream) {
+            Some(Some(Divergence { counts: None, .. })) => String::from("gone"),
+            Some(Some(Divergence { counts: Some(AheadBehind { ahead: 0, behind: 0 }), .. })) => String::from("="),
+            Some(Some(Divergence { counts: Some(AheadBehind { ahead, behind }), .. })) => {
+                let mut cell = Vec::new();
+                if *ahead > 0 {
+                    cell.push(format!("↑{ahead}"));
+                }
+                if *behind > 0 {
+                    cell.push(format!("↓{behind}"));
+                }
+                cell.join(" ")
+            },
+            Some(None) if !self.remotes.is_empty() => String::from("none"),
+            _ => String::from("-")
+        }
+    }
+
+    // Age of the remote data
+    fn table_fetched(&self) -> String {
+        if self.remotes.is_empty() {
+            return String::from("-");
+        }
+
+        self.last_fetch.map_or_else(|| String::from("never"), age_of)
+    }
+}
@@ -6,32 +6,57 @@ use crate::core::api::{
     scan_dirs,
     scan_all,
     list,
+    list_activity,
     add,
     remove_repos,
     remove_all,
     check_repos,
-    check_all
+    check_all,
+    prune_branches,
+    prune_all_branches,
+    sync_repos,
+    sync_all,
+    push_repos,
+    fetch_repos,
+    fetch_all,
+    exec_repos,
+    exec_all,
+    grep_repos,
+    grep_all,
+    log_repos,
+    log_all,
+    export,
+    import,
+    import_from,
+    push_all,
+    CheckOptions,
+    FetchMode,
+    OutputFormat,
+    GrepFormat,
+    DEFAULT_STALE_AGE
 };
+use crate::core::report::Condition;
 use crate::utils::{
     APP_NAME,
     TrackingFile,
     handle_error,
-    path_is_repo
+    path_is_repo,
+    set_colors
 };
-use crate::cli::{Cli, Commands};
+use crate::cli::{Cli, Commands, BranchesCommands};
 
 use std::fs::{self, File};
+use std::process;
+use std::num::NonZeroUsize;
 use std::io::Write as _;
 use std::fmt::Write as _;
 
 use clap::Parser;
 use colored::Colorize;
 
-#[tokio::main]
-async fn main() {
-    // Obtain the path to user's home directory,
-    // the tracking file and it's contents
-
+// Obtains the path to user's home directory and the tracking file,
+// prunes the entries that are no longer git repositories
+fn setup() -> (String, TrackingFile) {
     let mut tracking_file = TrackingFile {
         path: String::new(),
         contents: String::new()
@@ -50,7 +75,7 @@ async fn main() {
             match fs::create_dir_all(&app_data_dir) {
                 Ok(()) => (),
                 Err(e) => handle_error(format!("{app_data_dir}: {e}").as_str(), 1)
-            };
+            }
 
             if let Ok(str) = fs::read_to_string(&tracking_file.path) {
                 // Update the tracking file, push only the paths
@@ -58,14 +83,13 @@ async fn main() {
                 for line in str.lines() {
                     match path_is_repo(line) {
                         Ok(is_repo) => {
-                            if is_repo {
-                                if let Err(e) = writeln!(tracking_file.contents, "{line}") {
-                                    handle_error(e.to_string().as_str(), 1);
-                                }
+                            if is_repo
+                                && let Err(e) = writeln!(tracking_file.contents, "{line}") {
+                                handle_error(e.to_string().as_str(), 1);
                             }
                         },
                         Err(e) => handle_error(e.as_str(), 1)
-                    };
+                    }
                 }
 
                 // Write the final changes to the tracking file
@@ -77,7 +101,7 @@ async fn main() {
                         }
                     },
                     Err(e) => handle_error(format!("{}: {e}", tracking_file.path).as_str(), 1)
-                };
+                }
             }
         }
         else {
@@ -88,17 +112,26 @@ async fn main() {
         handle_error("Could not find the home directory", 1);
     }
 
+    (home_dir, tracking_file)
+}
+
+#[tokio::main]
+#[allow(clippy::too_many_lines)]
+async fn main() {
+    let (home_dir, tracking_file) = setup();
+
+    let cli = Cli::parse();
+    set_colors(cli.get_color());
+
     // Handle command-line interactions
-    match Cli::parse().get_command() {
+    match cli.get_command() {
         Commands::Scan { dirs, all, hidden, quiet} => {
-            let result: Result<String, String>;
-
-            if *all {
-                result = scan_all(home_dir, &tracking_file, *hidden);
+            let result = if *all {
+                scan_all(home_dir, &tracking_file, *hidden).await
             }
             else {
-                result = scan_dirs(dirs.to_owned(), &tracking_file, *hidden);
-            }
+                scan_dirs(dirs.to_owned(), &tracking_file, *hidden).await
+            };
 
             match result {
                 Ok(repos) => {
@@ -115,8 +148,16 @@ async fn main() {
                 Err(e) => handle_error(&e, 2)
             }
         },
-        Commands::List => {
-            if let Err(e) = list(tracking_file.contents.as_str()) {
+        Commands::List { sort, stale, jobs } => {
+            let result = if sort.is_none() && stale.is_none() {
+                list(tracking_file.contents.as_str())
+            }
+            else {
+                let jobs = jobs.map_or(CheckOptions::default().jobs, NonZeroUsize::get);
+                list_activity(tracking_file.contents.as_str(), *sort, *stale, jobs).await
+            };
+
+            if let Err(e) = result {
                 handle_error(&e, 3);
             }
         },
@@ -135,15 +176,243 @@ async fn main() {
                 handle_error(&e, 5);
             }
         },
-        Commands::Check { repos, all, status, remotes } => {
-            if *all {
-                if let Err(e) = check_all(&tracking_file, &[*status, *remotes]).await {
-                    handle_error(&e, 6);
-                }
+        Commands::Check {
+            repos,
+            all,
+            status,
+            remotes,
+            only,
+            sort,
+            progress,
+            jobs,
+            fetch_jobs,
+            no_fetch,
+            fetch_only_stale,
+            fetch_timeout,
+            all_remotes,
+            branches,
+            verbose,
+            table,
+            json,
+            exit_code
+        } => {
+            let mut options = CheckOptions {
+                fetch_jobs: fetch_jobs.get(),
+                all_remotes: *all_remotes,
+                verbose: *verbose,
+                only: only.clone(),
+                sort: *sort,
+                progress: *progress,
+                stale_branches: *branches,
+                ..CheckOptions::default()
+            };
+            if *status {
+                options.only.extend(Condition::LOCAL);
+            }
+            if *remotes {
+                options.only.extend(Condition::REMOTE);
+            }
+            // Asking for stale branches either way looks for them
+            if options.stale_branches.is_some() && !options.only.is_empty() {
+                options.only.push(Condition::StaleBranch);
+            }
+            else if options.only.contains(&Condition::StaleBranch) {
+                options.stale_branches = Some(DEFAULT_STALE_AGE);
+            }
+            if let Some(jobs) = jobs {
+                options.jobs = jobs.get();
             }
-            else if let Err(e) = check_repos(repos.to_owned(), &[*status, *remotes]).await {
-                handle_error(&e, 6);
+            if *table {
+                options.format = OutputFormat::Table;
+            }
+            else if *json {
+                options.format = OutputFormat::Json;
+            }
+            if *no_fetch {
+                options.fetch = FetchMode::Never;
+            }
+            else if let Some(max_age) = fetch_only_stale {
+                options.fetch = FetchMode::IfStale(*max_age);
+            }
+            for (remote, timeout) in fetch_timeout {
+                options.fetch_timeouts.set(remote.clone(), *timeout);
+            }
+
+            let result = if *all {
+                check_all(&tracking_file, &options).await
+            }
+            else {
+                check_repos(repos.to_owned(), &options).await
+            };
+
+            match result {
+                Ok(summary) => {
+                    if *exit_code && summary.exit_code() != 0 {
+                        process::exit(summary.exit_code());
+                    }
+                    else if summary.errors > 0 {
+                        process::exit(6);
+                    }
+                },
+                Err(e) => handle_error(&e, 6)
+            }
+        },
+        Commands::Sync { repos, all, dry_run, jobs, fetch_jobs, no_fetch, progress } => {
+            let mut options = CheckOptions {
+                fetch_jobs: fetch_jobs.get(),
+                progress: *progress,
+                ..CheckOptions::default()
+            };
+            if let Some(jobs) = jobs {
+                options.jobs = jobs.get();
+            }
+            if *no_fetch {
+                options.fetch = FetchMode::Never;
+            }
+
+            let result = if *all {
+                sync_all(&tracking_file, &options, *dry_run).await
+            }
+            else {
+                sync_repos(repos.to_owned(), &options, *dry_run).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 8);
+            }
+        },
+        Commands::Fetch { repos, all, jobs, fetch_jobs, fetch_timeout, progress } => {
+            let mut options = CheckOptions {
+                fetch_jobs: fetch_jobs.get(),
+                progress: *progress,
+                ..CheckOptions::default()
+            };
+            if let Some(jobs) = jobs {
+                options.jobs = jobs.get();
+            }
+            for (remote, timeout) in fetch_timeout {
+                options.fetch_timeouts.set(remote.clone(), *timeout);
+            }
+
+            let result = if *all {
+                fetch_all(&tracking_file, &options).await
+            }
+            else {
+                fetch_repos(repos.to_owned(), &options).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 10);
+            }
+        },
+        Commands::Exec { repos, all, jobs, prefix, command } => {
+            let result = if *all {
+                exec_all(&tracking_file, command.to_owned(), jobs.get(), *prefix).await
+            }
+            else {
+                exec_repos(repos.to_owned(), command.to_owned(), jobs.get(), *prefix).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 11);
+            }
+        },
+        Commands::Grep { pattern, repos, all, jobs, files_with_matches, count, json } => {
+            let format = if *files_with_matches {
+                GrepFormat::FilesWithMatches
+            }
+            else if *count {
+                GrepFormat::Count
+            }
+            else if *json {
+                GrepFormat::Json
+            }
+            else {
+                GrepFormat::Lines
+            };
+            let jobs = jobs.map_or(CheckOptions::default().jobs, NonZeroUsize::get);
+
+            let result = if *all {
+                grep_all(&tracking_file, pattern, jobs, format).await
+            }
+            else {
+                grep_repos(repos.to_owned(), pattern, jobs, format).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 12);
+            }
+        },
+        Commands::Log { repos, all, since, author, jobs } => {
+            let jobs = jobs.map_or(CheckOptions::default().jobs, NonZeroUsize::get);
+
+            let result = if *all {
+                log_all(&tracking_file, since, author.as_deref(), jobs).await
+            }
+            else {
+                log_repos(repos.to_owned(), since, author.as_deref(), jobs).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 13);
+            }
+        },
+        Commands::Export { format, output } => {
+            if let Err(e) = export(&tracking_file, home_dir.as_str(), *format, output.as_deref()).await {
+                handle_error(&e, 14);
+            }
+        },
+        Commands::Import { file, format, from, jobs, retries } => {
+            let result = if let Some(source) = from {
+                import_from(*source, file, &tracking_file, home_dir.as_str(), jobs.get(), *retries).await
+            }
+            else {
+                import(file, *format, &tracking_file, home_dir.as_str(), jobs.get(), *retries).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 15);
+            }
+        },
+        Commands::Push { repos, all, yes, jobs, fetch_jobs, no_fetch, progress } => {
+            let mut options = CheckOptions {
+                fetch_jobs: fetch_jobs.get(),
+                progress: *progress,
+                ..CheckOptions::default()
+            };
+            if let Some(jobs) = jobs {
+                options.jobs = jobs.get();
+            }
+            if *no_fetch {
+                options.fetch = FetchMode::Never;
+            }
+
+            let result = if *all {
+                push_all(&tracking_file, &options, *yes).await
+            }
+            else {
+                push_repos(repos.to_owned(), &options, *yes).await
+            };
+
+            if let Err(e) = result {
+                handle_error(&e, 9);
+            }
+        },
+        Commands::Branches { command } => match command {
+            BranchesCommands::Prune { repos, all, max_age, force, yes } => {
+                let max_age = max_age.unwrap_or(DEFAULT_STALE_AGE);
+
+                let result = if *all {
+                    prune_all_branches(&tracking_file, max_age, *force, *yes).await
+                }
+                else {
+                    prune_branches(repos.to_owned(), max_age, *force, *yes).await
+                };
+
+                if let Err(e) = result {
+                    handle_error(&e, 7);
+                }
             }
         }
-    };
+    }
 }
@@ -4,9 +4,16 @@
 #![allow(clippy::missing_panics_doc)]
 
 use std::fs;
+use std::borrow::Cow;
+use std::env;
+use std::io::{self, Write as _};
 use std::path::Path;
+use std::time::Duration;
 use std::process::{self, Command, Stdio};
 
+use clap::ColorChoice;
+use console::Term;
+
 pub const APP_NAME: &str = env!("CARGO_PKG_NAME");
 pub const SPINNER_TICK: u64 = 60;
 
@@ -70,7 +77,7 @@ pub fn repos_valid(repos: &[String]) -> Result<Vec<String>, String> {
                 }
             },
             Err(e) => return Err(e)
-        };
+        }
 
         // Check if the path contains valid UTF-8 characters
         // and make it absolute, if it does
@@ -92,6 +99,80 @@ pub fn repos_valid(repos: &[String]) -> Result<Vec<String>, String> {
     Ok(repos_abs)
 }
 
+/// Parses a human-readable duration such as `90s`, `30m`, `12h`, `7d` or `2w`,
+/// a number without a unit is treated as seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let number: u64 = number.parse().map_err(|_| format!("Invalid duration '{s}'"))?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return Err(format!("Invalid duration unit '{unit}', expected one of: s, m, h, d, w"))
+    };
+
+    Ok(Duration::from_secs(number * multiplier))
+}
+
+/// Formats a duration as a short human-readable age, using its largest unit
+#[allow(clippy::must_use_candidate)]
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400)
+    }
+}
+
+/// Enables or disables colored output, `Auto` colors only terminals and
+/// only if the `NO_COLOR` environment variable is unset or empty, the
+/// standard output and the standard error are decided on separately
+pub fn set_colors(choice: ColorChoice) {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let (stdout, stderr) = match choice {
+        ColorChoice::Always => (true, true),
+        ColorChoice::Never => (false, false),
+        ColorChoice::Auto => (!no_color && Term::stdout().is_term(), !no_color && Term::stderr().is_term())
+    };
+
+    // Styling from `colored` can't tell the streams apart,
+    // `stderr_text` removes it from the standard error
+    colored::control::set_override(stdout);
+    console::set_colors_enabled(stdout);
+    console::set_colors_enabled_stderr(stderr);
+}
+
+/// Removes the styling from text meant for the standard
+/// error unless colors are enabled there, see `set_colors`
+#[must_use]
+pub fn stderr_text(text: &str) -> Cow<'_, str> {
+    if console::colors_enabled_stderr() {
+        Cow::Borrowed(text)
+    }
+    else {
+        console::strip_ansi_codes(text)
+    }
+}
+
+/// Asks the user a yes/no question, anything but `y` or `yes` counts as no
+pub fn confirm(question: &str) -> Result<bool, String> {
+    print!("{question} [y/N] ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).map_err(|e| e.to_string())?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 /// Prints given error message to the standard error with application name
 /// and then exits the application with specified error code
 pub fn handle_error(error: &str, code: i32) {
@@ -1,8 +1,31 @@
 mod common;
 
-use git_conform::core::api::{check_repos, check_all};
+use common::FakeBackend;
+
+use git_conform::core::api::{
+    check_repos,
+    check_all,
+    check_all_with,
+    CheckOptions,
+    FetchMode
+};
+use git_conform::core::git::{
+    GitBackend,
+    GitCli,
+    Head,
+    Operation
+};
+use git_conform::core::report::{CheckSummary, Condition, EXIT_DIRTY, EXIT_UNPUSHED, EXIT_BEHIND, EXIT_ERRORS};
 use git_conform::utils::TrackingFile;
 
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::Ordering;
+
+// Exit status of a check of repositories faked by `FakeBackend`
+const EXIT_FAKE: i32 = EXIT_DIRTY | EXIT_UNPUSHED | EXIT_BEHIND;
+
 #[tokio::test]
 async fn case_check_repos_real() {
     let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
@@ -13,7 +36,7 @@ async fn case_check_repos_real() {
         repos.push(format!("{tests_dir}/repo{n}"));
         repos.push(format!("{tests_dir}/.hidden/repo{n}"));
     }
-    assert_eq!(check_repos(repos, &[true, true]).await, Ok(()));
+    assert!(check_repos(repos, &CheckOptions::default()).await.is_ok());
 }
 
 #[tokio::test]
@@ -26,7 +49,7 @@ async fn case_check_repos_fake() {
         repos.push(format!("{tests_dir}/fake_repo{n}"));
         repos.push(format!("{tests_dir}/.hidden/fake_repo{n}"));
     }
-    assert_eq!(check_repos(repos, &[true, true]).await, Err(String::from("Repositories validation failed")));
+    assert_eq!(check_repos(repos, &CheckOptions::default()).await, Err(String::from("Repositories validation failed")));
 }
 
 #[tokio::test]
@@ -39,7 +62,7 @@ async fn case_check_repos_regular_dirs() {
         repos.push(format!("{tests_dir}/dir{n}"));
         repos.push(format!("{tests_dir}/.hidden/dir{n}"));
     }
-    assert_eq!(check_repos(repos, &[true, true]).await, Err(String::from("Repositories validation failed")));
+    assert_eq!(check_repos(repos, &CheckOptions::default()).await, Err(String::from("Repositories validation failed")));
 }
 
 #[tokio::test]
@@ -52,7 +75,7 @@ async fn case_check_repos_non_existent() {
         format!("lvdslns"),
         format!("fjioadbaob")
     ];
-    assert_eq!(check_repos(repos, &[true, true]).await, Err(String::from("Repositories validation failed")));
+    assert_eq!(check_repos(repos, &CheckOptions::default()).await, Err(String::from("Repositories validation failed")));
 }
 
 #[tokio::test]
@@ -64,7 +87,7 @@ async fn case_check_repos_files() {
     for n in 1..=3 {
         repos.push(format!("{tests_dir}/file{n}"));
     }
-    assert_eq!(check_repos(repos, &[true, true]).await, Err(String::from("Repositories validation failed")));
+    assert_eq!(check_repos(repos, &CheckOptions::default()).await, Err(String::from("Repositories validation failed")));
 }
 
 #[tokio::test]
@@ -74,7 +97,7 @@ async fn case_check_all() {
     tracking_file.contents = format!("{tests_dir}/repo1\n{tests_dir}/repo2\n{tests_dir}/repo3");
 
     // The function executes without errors
-    assert_eq!(check_all(&tracking_file, &[true, true]).await, Ok(()));
+    assert!(check_all(&tracking_file, &CheckOptions::default()).await.is_ok());
 }
 
 #[tokio::test]
@@ -85,5 +108,154 @@ async fn case_check_all_empty_tracking_file() {
     };
 
     // The function throws an error
-    assert_eq!(check_all(&tracking_file, &[true, true]).await, Err(String::from("No repository is being tracked")));
+    assert_eq!(check_all(&tracking_file, &CheckOptions::default()).await, Err(String::from("No repository is being tracked")));
+}
+
+#[tokio::test]
+async fn case_check_all_fake_backend() {
+    let tracking_file = common::fake_tracking();
+    let backend = Arc::new(FakeBackend::default());
+
+    // The function executes without errors using only the injected backend
+    assert_eq!(check_all_with(&tracking_file, &CheckOptions::default(), backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_FAKE));
+
+    // Every remote of every repository has been fetched
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn case_check_all_fetch_limit() {
+    let tracking_file = TrackingFile {
+        path: String::new(),
+        contents: (1..=8)
+            .map(|n| format!("/fake/repo{n}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    let backend = Arc::new(FakeBackend::default());
+    let options = CheckOptions {
+        jobs: 8,
+        fetch_jobs: 2,
+        ..CheckOptions::default()
+    };
+
+    // The function executes without errors
+    assert_eq!(check_all_with(&tracking_file, &options, backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_FAKE));
+
+    // All remotes have been fetched, never more than allowed at once
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 16);
+    assert!(backend.max_running_fetches.load(Ordering::SeqCst) <= 2);
+}
+
+#[tokio::test]
+async fn case_check_all_no_fetch() {
+    let tracking_file = common::fake_tracking();
+    let backend = Arc::new(FakeBackend::default());
+    let options = CheckOptions {
+        fetch: FetchMode::Never,
+        ..CheckOptions::default()
+    };
+
+    // The function executes without errors and doesn't fetch anything
+    assert_eq!(check_all_with(&tracking_file, &options, backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_FAKE));
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn case_check_all_fetch_only_stale() {
+    let tracking_file = common::fake_tracking();
+    let backend = Arc::new(FakeBackend::default());
+
+    // The remote data is an hour old, so it's still fresh
+    let options = CheckOptions {
+        fetch: FetchMode::IfStale(Duration::from_hours(2)),
+        ..CheckOptions::default()
+    };
+    assert_eq!(check_all_with(&tracking_file, &options, backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_FAKE));
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 0);
+
+    // The remote data is an hour old, so it's stale
+    let options = CheckOptions {
+        fetch: FetchMode::IfStale(Duration::from_mins(30)),
+        ..CheckOptions::default()
+    };
+    assert_eq!(check_all_with(&tracking_file, &options, backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_FAKE));
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn case_repo_state() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Repository with a stash, a detached HEAD and a bisect in progress
+    let repo = format!("{tests_dir}/state_repo");
+    common::init_repo(repo.as_str());
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["commit", "--allow-empty", "-m", "second"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    // Nothing unusual yet
+    let state = GitCli.state(repo.as_str()).await.unwrap();
+    assert!(matches!(state.head, Head::Branch(_)));
+    assert_eq!(state.operation, None);
+    assert_eq!(state.stashes, 0);
+
+    fs::write(format!("{repo}/file"), "changes").unwrap();
+    for args in [
+        vec!["add", "file"],
+        vec!["stash"],
+        vec!["checkout", "--detach", "HEAD~1"],
+        vec!["bisect", "start"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    let state = GitCli.state(repo.as_str()).await.unwrap();
+    assert!(matches!(state.head, Head::Detached(_)));
+    assert_eq!(state.operation, Some(Operation::Bisect));
+    assert_eq!(state.stashes, 1);
+}
+
+#[tokio::test]
+async fn case_check_exit_code() {
+    let tracking_file = common::fake_tracking();
+    let backend = Arc::new(FakeBackend::default());
+
+    // Every condition met by the repositories is in the summary
+    let summary = check_all_with(&tracking_file, &CheckOptions::default(), backend.clone()).await.unwrap();
+    assert_eq!(summary.conditions, vec![Condition::Dirty, Condition::Unpushed, Condition::Behind, Condition::MissingUpstream]);
+    assert_eq!(summary.errors, 0);
+
+    // Only the selected conditions affect the exit status
+    let options = CheckOptions {
+        only: vec![Condition::Dirty, Condition::NoRemote],
+        ..CheckOptions::default()
+    };
+    assert_eq!(check_all_with(&tracking_file, &options, backend.clone()).await.map(|s| s.exit_code()), Ok(EXIT_DIRTY));
+
+    // Errors are reported along with the findings
+    let summary = CheckSummary {
+        conditions: vec![Condition::Behind, Condition::Stashed],
+        errors: 1
+    };
+    assert_eq!(summary.exit_code(), EXIT_BEHIND | EXIT_ERRORS);
+    assert_eq!(CheckSummary::default().exit_code(), 0);
+}
+
+#[tokio::test]
+async fn case_check_errors() {
+    let tracking_file = common::fake_tracking();
+    let backend = Arc::new(FakeBackend {
+        broken_repo: Some(String::from("/fake/repo2")),
+        ..FakeBackend::default()
+    });
+
+    // The failure is counted and doesn't stop the other repository from being inspected
+    let summary = check_all_with(&tracking_file, &CheckOptions::default(), backend.clone()).await.unwrap();
+    assert_eq!(summary.errors, 1);
+    assert_eq!(summary.exit_code(), EXIT_FAKE | EXIT_ERRORS);
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 2);
 }
@@ -1,7 +1,26 @@
+#![allow(clippy::unnecessary_semicolon)]
+
+use git_conform::core::git::{
+    GitBackend,
+    FetchOutcome,
+    FileStatus,
+    Branch,
+    RemoteBranch,
+    RepoState,
+    Head,
+    Commit,
+    PushOutcome,
+    GrepMatch,
+    LogEntry
+};
 use git_conform::utils::{APP_NAME, TrackingFile};
 
+use async_trait::async_trait;
+
 use std::fs::{self, File};
 use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[allow(unused_assignments)]
 pub fn setup() -> Result<(String, TrackingFile, String), String> {
@@ -108,3 +127,217 @@ pub fn setup() -> Result<(String, TrackingFile, String), String> {
 
     Ok((home_dir, tracking_file, tests_dir))
 }
+
+// Runs git in a given repository with a fixed identity, fails if git does
+#[allow(dead_code)]
+pub fn git(repo: &str, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-C", repo, "-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "git {} failed in {repo}", args.join(" "));
+}
+
+// Runs git in a given repository and returns its trimmed output, fails if git does
+#[allow(dead_code)]
+pub fn git_output(repo: &str, args: &[&str]) -> String {
+    let output = Command::new("git").args(["-C", repo]).args(args).output().unwrap();
+
+    assert!(output.status.success(), "git {} failed in {repo}", args.join(" "));
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+// Creates an empty repository with a `main` branch at a given
+// path, removing whatever an earlier run has left there
+#[allow(dead_code)]
+pub fn init_repo(path: &str) {
+    let _ = fs::remove_dir_all(path);
+    fs::create_dir_all(path).unwrap();
+    git(path, &["init", "--initial-branch", "main"]);
+}
+
+// Tracking file listing two repositories that exist only for `FakeBackend`
+#[allow(dead_code)]
+pub fn fake_tracking() -> TrackingFile {
+    TrackingFile {
+        path: String::new(),
+        contents: String::from("/fake/repo1\n/fake/repo2")
+    }
+}
+
+// Backend faking a repository with two branches and two remotes,
+// counts the number of performed and concurrently running fetches,
+// fails to inspect the repository at `broken_repo`
+#[derive(Default)]
+#[allow(dead_code)]
+pub struct FakeBackend {
+    pub broken_repo: Option<String>,
+    pub fetches: AtomicUsize,
+    pub running_fetches: AtomicUsize,
+    pub max_running_fetches: AtomicUsize
+}
+
+#[async_trait]
+impl GitBackend for FakeBackend {
+    async fn is_repo(&self, _path: &str) -> Result<bool, String> {
+        Ok(true)
+    }
+
+    async fn status(&self, repo: &str) -> Result<Vec<FileStatus>, String> {
+        if self.broken_repo.as_deref() == Some(repo) {
+            return Err(format!("{repo}: index file corrupt"));
+        }
+
+        Ok(vec![FileStatus {
+            path: String::from("untracked"),
+            code: String::from("??"),
+            renamed_from: None,
+            conflicted: false
+        }])
+    }
+
+    async fn branches(&self, _repo: &str) -> Result<Vec<Branch>, String> {
+        let origin_main = RemoteBranch {
+            remote: String::from("origin"),
+            name: String::from("origin/main")
+        };
+
+        Ok(vec![
+            Branch {
+                name: String::from("main"),
+                upstream: Some(origin_main.clone()),
+                push: Some(origin_main),
+                last_commit: Some(SystemTime::now() - Duration::from_hours(2))
+            },
+            Branch {
+                name: String::from("dev"),
+                upstream: None,
+                push: None,
+                last_commit: Some(SystemTime::now() - Duration::from_hours(24 * 100))
+            }
+        ])
+    }
+
+    async fn state(&self, _repo: &str) -> Result<RepoState, String> {
+        Ok(RepoState {
+            head: Head::Branch(String::from("main")),
+            operation: None,
+            stashes: 0
+        })
+    }
+
+    async fn remotes(&self, _repo: &str) -> Result<Vec<String>, String> {
+        Ok(vec![String::from("origin"), String::from("upstream")])
+    }
+
+    async fn fetch(&self, _repo: &str, _remote: &str, _timeout: Duration) -> Result<FetchOutcome, String> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+
+        let running = self.running_fetches.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running_fetches.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.running_fetches.fetch_sub(1, Ordering::SeqCst);
+
+        Ok(FetchOutcome::Fetched)
+    }
+
+    async fn last_fetch(&self, _repo: &str) -> Result<Option<SystemTime>, String> {
+        Ok(Some(SystemTime::now() - Duration::from_hours(1)))
+    }
+
+    async fn fetch_timeouts(&self, _repo: &str) -> Result<Vec<(Option<String>, Duration)>, String> {
+        Ok(Vec::new())
+    }
+
+    async fn last_commit(&self, _repo: &str) -> Result<Option<Commit>, String> {
+        Ok(Some(Commit {
+            time: SystemTime::now() - Duration::from_hours(2),
+            author: String::from("test")
+        }))
+    }
+
+    async fn last_modified(&self, _repo: &str) -> Result<Option<SystemTime>, String> {
+        Ok(Some(SystemTime::now() - Duration::from_mins(5)))
+    }
+
+    async fn ahead_behind(&self, _repo: &str, _branch: &str, remote_branch: &str) -> Result<Option<(u32, u32)>, String> {
+        if remote_branch.starts_with("upstream/") {
+            return Ok(None);
+        }
+        Ok(Some((1, 2)))
+    }
+
+    async fn default_branch(&self, _repo: &str) -> Result<Option<String>, String> {
+        Ok(Some(String::from("main")))
+    }
+
+    async fn merged_branches(&self, _repo: &str, _base: &str) -> Result<Vec<String>, String> {
+        Ok(vec![String::from("main")])
+    }
+
+    async fn delete_branch(&self, _repo: &str, _branch: &str, _force: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn fast_forward(&self, _repo: &str, _target: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn push(&self, _repo: &str, _branch: &str, _remote: &str, _remote_branch: &str) -> Result<PushOutcome, String> {
+        Ok(PushOutcome::Pushed)
+    }
+
+    async fn grep(&self, repo: &str, pattern: &str) -> Result<Vec<GrepMatch>, String> {
+        if self.broken_repo.as_deref() == Some(repo) {
+            return Err(String::from("fatal: broken"));
+        }
+
+        Ok(vec![GrepMatch {
+            path: String::from("src/main.rs"),
+            line: 1,
+            text: format!("use {pattern};")
+        }])
+    }
+
+    async fn remote_url(&self, _repo: &str, remote: &str) -> Result<Option<String>, String> {
+        Ok(Some(format!("https://example.com/{remote}/repo.git")))
+    }
+
+    async fn head_tags(&self, _repo: &str) -> Result<Vec<String>, String> {
+        Ok(vec![String::from("v1.0.0")])
+    }
+
+    async fn clone(&self, _url: &str, _path: &str, _remote: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn add_remote(&self, _repo: &str, _remote: &str, _url: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn set_remote_url(&self, _repo: &str, _remote: &str, _url: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn checkout(&self, _repo: &str, _rev: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn log(&self, repo: &str, _since: &str, _author: Option<&str>) -> Result<Vec<LogEntry>, String> {
+        if self.broken_repo.as_deref() == Some(repo) {
+            return Err(String::from("fatal: broken"));
+        }
+
+        Ok(vec![LogEntry {
+            hash: String::from("abc1234"),
+            time: SystemTime::now() - Duration::from_hours(2),
+            date: String::from("2024-06-01 12:00"),
+            author: String::from("test"),
+            subject: String::from("Fix the tests")
+        }])
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use git_conform::core::api::{exec_repos, exec_all};
+use git_conform::utils::TrackingFile;
+
+use std::fs;
+use std::path::Path;
+
+#[tokio::test]
+async fn case_exec_repos() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    let repos: Vec<String> = (1..=3).map(|n| format!("{tests_dir}/repo{n}")).collect();
+    for repo in &repos {
+        let _ = fs::remove_file(format!("{repo}/exec_marker"));
+    }
+
+    // The command runs inside every repository
+    let command = vec![String::from("touch"), String::from("exec_marker")];
+    assert_eq!(exec_repos(repos.clone(), command, 2, false).await, Ok(()));
+    for repo in &repos {
+        assert!(Path::new(format!("{repo}/exec_marker").as_str()).exists());
+        fs::remove_file(format!("{repo}/exec_marker")).unwrap();
+    }
+
+    // Failures are counted
+    let command = vec![String::from("false")];
+    assert_eq!(
+        exec_repos(repos.clone(), command, 1, true).await,
+        Err(String::from("The command failed in 3 of 3 repositories"))
+    );
+
+    // A missing command is a failure as well
+    let command = vec![String::from("git-conform-no-such-command")];
+    assert!(exec_repos(repos, command, 1, false).await.is_err());
+}
+
+#[tokio::test]
+async fn case_exec_repos_fake() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    // The function throws an error
+    let command = vec![String::from("true")];
+    assert!(exec_repos(vec![format!("{tests_dir}/fake_repo1")], command, 1, false).await.is_err());
+}
+
+#[tokio::test]
+async fn case_exec_all_empty_tracking_file() {
+    let tracking_file = TrackingFile {
+        path: String::new(),
+        contents: String::new()
+    };
+
+    // The function throws an error
+    assert_eq!(
+        exec_all(&tracking_file, vec![String::from("true")], 1, false).await,
+        Err(String::from("No repository is being tracked"))
+    );
+}
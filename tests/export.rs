@@ -0,0 +1,34 @@
+mod common;
+
+use common::FakeBackend;
+
+use git_conform::core::api::export_with;
+use git_conform::utils::TrackingFile;
+
+use std::fs;
+
+#[tokio::test]
+async fn case_export() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let tracking_file = TrackingFile {
+        path: String::new(),
+        contents: String::from("/home/user/src/repo1\n/srv/repo2")
+    };
+    let output = format!("{tests_dir}/manifest.json");
+
+    // The format follows the file extension
+    assert_eq!(export_with(&tracking_file, "/home/user", None, Some(output.as_str()), &FakeBackend::default()).await, Ok(()));
+
+    let manifest: serde_json::Value = serde_json::from_str(fs::read_to_string(&output).unwrap().as_str()).unwrap();
+    let repos = manifest["repos"].as_array().unwrap();
+
+    // Paths are relative to the home directory when inside it
+    assert_eq!(repos[0]["path"], "src/repo1");
+    assert_eq!(repos[1]["path"], "/srv/repo2");
+
+    assert_eq!(repos[0]["branch"], "main");
+    assert_eq!(repos[0]["tags"][0], "v1.0.0");
+    assert_eq!(repos[0]["remotes"][1]["name"], "upstream");
+    assert_eq!(repos[0]["remotes"][1]["url"], "https://example.com/upstream/repo.git");
+}
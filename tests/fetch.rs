@@ -0,0 +1,121 @@
+mod common;
+
+use common::FakeBackend;
+
+use git_conform::core::api::{fetch_all_with, CheckOptions, FetchMode};
+use git_conform::core::git::{GitBackend, GitCli, FetchOutcome};
+use git_conform::utils::TrackingFile;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt as _;
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::Ordering;
+
+#[tokio::test]
+async fn case_fetch_auth_failure() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Repository with remotes faking a rejected authentication and some other failure
+    let repo = format!("{tests_dir}/auth_repo");
+    common::init_repo(repo.as_str());
+    for script in [("auth", "fatal: Authentication failed for 'https://example.com/'"), ("other", "fatal: unable to access")] {
+        let script_path = format!("{tests_dir}/{}.sh", script.0);
+        fs::write(&script_path, format!("#!/bin/sh\necho \"{}\" >&2\nexit 128\n", script.1)).unwrap();
+        fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let auth_url = format!("ext::{tests_dir}/auth.sh");
+    let other_url = format!("ext::{tests_dir}/other.sh");
+    for args in [
+        vec!["config", "protocol.ext.allow", "always"],
+        vec!["remote", "add", "auth", auth_url.as_str()],
+        vec!["remote", "add", "other", other_url.as_str()]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    // Authentication failures are told apart from other failures
+    assert!(matches!(
+        GitCli.fetch(repo.as_str(), "auth", Duration::from_secs(10)).await,
+        Ok(FetchOutcome::AuthFailed(_))
+    ));
+    assert!(matches!(
+        GitCli.fetch(repo.as_str(), "other", Duration::from_secs(10)).await,
+        Ok(FetchOutcome::Failed(_))
+    ));
+}
+
+#[tokio::test]
+async fn case_fetch_ssh_command() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Repository whose configured ssh command records its arguments
+    let repo = format!("{tests_dir}/ssh_repo");
+    common::init_repo(repo.as_str());
+    let script_path = format!("{tests_dir}/ssh.sh");
+    let args_path = format!("{tests_dir}/ssh_args");
+    let _ = fs::remove_file(&args_path);
+    fs::write(&script_path, format!("#!/bin/sh\necho \"$@\" > {args_path}\nexit 255\n")).unwrap();
+    fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755)).unwrap();
+    for args in [
+        vec!["config", "core.sshCommand", script_path.as_str()],
+        vec!["remote", "add", "origin", "ssh://git@example.invalid/repo.git"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    // The configured command is used, with prompts disabled
+    assert!(matches!(
+        GitCli.fetch(repo.as_str(), "origin", Duration::from_secs(10)).await,
+        Ok(FetchOutcome::Failed(_))
+    ));
+    assert!(fs::read_to_string(&args_path).unwrap().contains("-o BatchMode=yes"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn case_fetch_all() {
+    let tracking_file = TrackingFile {
+        path: String::new(),
+        contents: (1..=8)
+            .map(|n| format!("/fake/repo{n}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    let backend = Arc::new(FakeBackend::default());
+    let options = CheckOptions {
+        jobs: 8,
+        fetch_jobs: 2,
+        fetch: FetchMode::Never,
+        ..CheckOptions::default()
+    };
+
+    // Fetching happens regardless of the fetch mode
+    assert_eq!(fetch_all_with(&tracking_file, &options, backend.clone()).await, Ok(()));
+
+    // All remotes have been fetched, never more than allowed at once
+    assert_eq!(backend.fetches.load(Ordering::SeqCst), 16);
+    assert!(backend.max_running_fetches.load(Ordering::SeqCst) <= 2);
+}
+
+#[tokio::test]
+async fn case_fetch_timeouts_config() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let repo = format!("{tests_dir}/timeouts_repo");
+    common::init_repo(repo.as_str());
+    for args in [
+        vec!["config", "conform.fetchTimeout", "30s"],
+        vec!["config", "remote.Up.stream.conformFetchTimeout", "2m"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    // Remote names keep their case and dots
+    assert_eq!(GitCli.fetch_timeouts(repo.as_str()).await, Ok(vec![
+        (None, Duration::from_secs(30)),
+        (Some(String::from("Up.stream")), Duration::from_mins(2))
+    ]));
+
+    common::git(repo.as_str(), &["config", "conform.fetchTimeout", "soon"]);
+    assert!(GitCli.fetch_timeouts(repo.as_str()).await.is_err());
+}
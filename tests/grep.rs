@@ -0,0 +1,45 @@
+mod common;
+
+use common::FakeBackend;
+
+use git_conform::core::api::{grep_all_with, grep_repos, GrepFormat};
+
+use std::fs;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn case_grep_all() {
+    let tracking_file = common::fake_tracking();
+
+    // Every output format works
+    for format in [GrepFormat::Lines, GrepFormat::FilesWithMatches, GrepFormat::Count, GrepFormat::Json] {
+        assert_eq!(grep_all_with(&tracking_file, "deprecated", 2, format, Arc::new(FakeBackend::default())).await, Ok(()));
+    }
+
+    // Repositories that couldn't be searched are counted
+    let backend = Arc::new(FakeBackend {
+        broken_repo: Some(String::from("/fake/repo2")),
+        ..FakeBackend::default()
+    });
+    assert_eq!(
+        grep_all_with(&tracking_file, "deprecated", 2, GrepFormat::Lines, backend).await,
+        Err(String::from("Could not search 1 repositories"))
+    );
+}
+
+#[tokio::test]
+async fn case_grep_repos_real() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let repo = format!("{tests_dir}/grep_repo");
+    common::init_repo(repo.as_str());
+    fs::write(format!("{repo}/lib.rs"), "fn old_api() {}\nfn new_api() {}\n").unwrap();
+    common::git(repo.as_str(), &["add", "lib.rs"]);
+
+    // Matches and no matches alike are fine
+    assert_eq!(grep_repos(vec![repo.clone()], "old_api", 1, GrepFormat::Lines).await, Ok(()));
+    assert_eq!(grep_repos(vec![repo.clone()], "missing_api", 1, GrepFormat::Json).await, Ok(()));
+
+    // An invalid pattern makes git grep fail
+    assert!(grep_repos(vec![repo], "[", 1, GrepFormat::Count).await.is_err());
+}
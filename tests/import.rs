@@ -0,0 +1,192 @@
+mod common;
+
+use git_conform::core::api::{import, import_from};
+use git_conform::core::manifest::{Manifest, ManifestRepo, ManifestRemote, ManifestFormat, ImportSource};
+use git_conform::utils::TrackingFile;
+
+use std::fs;
+
+#[tokio::test]
+async fn case_import() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    let import_dir = format!("{tests_dir}/import");
+    let _ = fs::remove_dir_all(&import_dir);
+    fs::create_dir_all(&import_dir).unwrap();
+
+    // Remote with a branch and a tag, along with an empty mirror
+    let source = format!("{import_dir}/source");
+    let remote = format!("{import_dir}/remote.git");
+    let mirror = format!("{import_dir}/mirror.git");
+    common::init_repo(source.as_str());
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["tag", "v1"],
+        vec!["switch", "--create", "dev"],
+        vec!["commit", "--allow-empty", "-m", "second"]
+    ] {
+        common::git(source.as_str(), args.as_slice());
+    }
+    common::git(import_dir.as_str(), &["clone", "--bare", source.as_str(), remote.as_str()]);
+    common::git(import_dir.as_str(), &["init", "--bare", mirror.as_str()]);
+
+    let origin = ManifestRemote {
+        name: String::from("origin"),
+        url: format!("file://{remote}")
+    };
+    let mut manifest = Manifest {
+        repos: vec![
+            ManifestRepo {
+                path: String::from("import/clone"),
+                branch: Some(String::from("dev")),
+                remotes: vec![
+                    origin.clone(),
+                    ManifestRemote {
+                        name: String::from("mirror"),
+                        url: format!("file://{mirror}")
+                    }
+                ],
+                tags: Vec::new()
+            },
+            ManifestRepo {
+                path: String::from("import/tagged"),
+                branch: None,
+                remotes: vec![origin],
+                tags: vec![String::from("v1")]
+            },
+            ManifestRepo {
+                path: String::from("import/no_remote"),
+                ..ManifestRepo::default()
+            }
+        ]
+    };
+
+    let manifest_path = format!("{import_dir}/manifest.toml");
+    let tracking_path = format!("{import_dir}/tracked");
+    let tracking_file = |contents| TrackingFile {
+        path: tracking_path.clone(),
+        contents
+    };
+
+    // A repository without remotes can't be cloned
+    fs::write(&manifest_path, manifest.to_string(ManifestFormat::Toml).unwrap()).unwrap();
+    assert_eq!(
+        import(manifest_path.as_str(), None, &tracking_file(String::new()), tests_dir.as_str(), 2, 0).await,
+        Err(String::from("Could not import 1 repositories"))
+    );
+
+    // The recorded branch, tag and remotes are there
+    let clone = format!("{import_dir}/clone");
+    let tagged = format!("{import_dir}/tagged");
+    assert_eq!(common::git_output(clone.as_str(), &["branch", "--show-current"]), "dev");
+    assert_eq!(common::git_output(clone.as_str(), &["remote"]), "mirror\norigin");
+    assert_eq!(common::git_output(tagged.as_str(), &["describe", "--tags", "--exact-match"]), "v1");
+
+    // Only the imported repositories are tracked
+    let tracked = fs::read_to_string(&tracking_path).unwrap();
+    assert_eq!(tracked, format!("{clone}\n{tagged}\n"));
+
+    // Existing repositories get their remotes reconciled and aren't tracked twice
+    manifest.repos.pop();
+    manifest.repos[0].remotes[1].url = format!("file://{remote}");
+    fs::write(&manifest_path, manifest.to_string(ManifestFormat::Toml).unwrap()).unwrap();
+    assert_eq!(import(manifest_path.as_str(), None, &tracking_file(tracked.clone()), tests_dir.as_str(), 2, 0).await, Ok(()));
+    assert_eq!(common::git_output(clone.as_str(), &["remote", "get-url", "mirror"]), format!("file://{remote}"));
+    assert_eq!(fs::read_to_string(&tracking_path).unwrap(), tracked);
+}
+
+#[tokio::test]
+async fn case_import_branch_on_other_remote() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    let import_dir = format!("{tests_dir}/import_fork");
+    let _ = fs::remove_dir_all(&import_dir);
+    fs::create_dir_all(&import_dir).unwrap();
+
+    // Fork with a branch the original remote doesn't have
+    let source = format!("{import_dir}/source");
+    let remote = format!("{import_dir}/remote.git");
+    let fork = format!("{import_dir}/fork.git");
+    common::init_repo(source.as_str());
+    common::git(source.as_str(), &["commit", "--allow-empty", "-m", "first"]);
+    common::git(import_dir.as_str(), &["clone", "--bare", source.as_str(), remote.as_str()]);
+    common::git(source.as_str(), &["switch", "--create", "feature"]);
+    common::git(source.as_str(), &["commit", "--allow-empty", "-m", "second"]);
+    common::git(import_dir.as_str(), &["clone", "--bare", source.as_str(), fork.as_str()]);
+
+    let manifest = Manifest {
+        repos: vec![ManifestRepo {
+            path: String::from("import_fork/clone"),
+            branch: Some(String::from("feature")),
+            remotes: vec![
+                ManifestRemote {
+                    name: String::from("origin"),
+                    url: format!("file://{remote}")
+                },
+                ManifestRemote {
+                    name: String::from("fork"),
+                    url: format!("file://{fork}")
+                }
+            ],
+            tags: Vec::new()
+        }]
+    };
+
+    let manifest_path = format!("{import_dir}/manifest.toml");
+    fs::write(&manifest_path, manifest.to_string(ManifestFormat::Toml).unwrap()).unwrap();
+    let tracking_file = TrackingFile {
+        path: format!("{import_dir}/tracked"),
+        contents: String::new()
+    };
+
+    assert_eq!(import(manifest_path.as_str(), None, &tracking_file, tests_dir.as_str(), 1, 0).await, Ok(()));
+
+    // The branch has been checked out from the fork
+    let clone = format!("{import_dir}/clone");
+    assert_eq!(common::git_output(clone.as_str(), &["branch", "--show-current"]), "feature");
+    assert_eq!(common::git_output(clone.as_str(), &["rev-parse", "--abbrev-ref", "feature@{upstream}"]), "fork/feature");
+}
+
+#[tokio::test]
+async fn case_import_from_gita() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    let import_dir = format!("{tests_dir}/import_gita");
+    let _ = fs::remove_dir_all(&import_dir);
+    fs::create_dir_all(&import_dir).unwrap();
+
+    // Gita list with an existing repository and a missing one
+    let existing = format!("{import_dir}/existing");
+    common::init_repo(existing.as_str());
+    let repos_csv = format!("{import_dir}/repos.csv");
+    fs::write(&repos_csv, "existing,existing,,\nmissing,missing,,\n").unwrap();
+
+    let tracking_path = format!("{import_dir}/tracked");
+    let tracking_file = TrackingFile {
+        path: tracking_path.clone(),
+        contents: String::new()
+    };
+
+    // The missing repository can't be cloned, but that isn't an error
+    assert_eq!(
+        import_from(ImportSource::Gita, repos_csv.as_str(), &tracking_file, tests_dir.as_str(), 1, 0).await,
+        Ok(())
+    );
+    assert_eq!(fs::read_to_string(&tracking_path).unwrap(), format!("{existing}\n"));
+}
+
+#[tokio::test]
+async fn case_import_invalid_manifest() {
+    let (_home_dir, _tracking_file, tests_dir) = common::setup().unwrap();
+
+    let manifest_path = format!("{tests_dir}/invalid_manifest.json");
+    fs::write(&manifest_path, "{ \"repos\": 1 }").unwrap();
+
+    let tracking_file = TrackingFile {
+        path: format!("{tests_dir}/invalid_tracked"),
+        contents: String::new()
+    };
+
+    // The function throws an error
+    assert!(import(manifest_path.as_str(), None, &tracking_file, tests_dir.as_str(), 1, 0).await.is_err());
+}
@@ -1,7 +1,39 @@
-use git_conform::core::api::list;
+mod common;
+
+use git_conform::core::api::{list, list_activity, ListSort};
+use git_conform::core::git::{GitBackend, GitCli};
+
+use std::fs;
 
 #[test]
 fn case_list_empty() {
     // The function throws an error
     assert_eq!(list(""), Err(String::from("No repository is being tracked")));
 }
+
+#[tokio::test]
+async fn case_list_activity_empty() {
+    // The function throws an error
+    assert_eq!(list_activity("", Some(ListSort::Activity), None, 1).await, Err(String::from("No repository is being tracked")));
+}
+
+#[tokio::test]
+async fn case_repo_activity() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let repo = format!("{tests_dir}/activity_repo");
+    common::init_repo(repo.as_str());
+
+    // Nothing has happened in the repository yet
+    assert_eq!(GitCli.last_commit(repo.as_str()).await, Ok(None));
+    assert_eq!(GitCli.last_modified(repo.as_str()).await, Ok(None));
+
+    common::git(repo.as_str(), &["commit", "--allow-empty", "-m", "first"]);
+    let commit = GitCli.last_commit(repo.as_str()).await.unwrap().unwrap();
+    assert_eq!(commit.author, "test");
+
+    // Untracked files count as local modifications
+    fs::write(format!("{repo}/file"), "changes").unwrap();
+    let modified = GitCli.last_modified(repo.as_str()).await.unwrap().unwrap();
+    assert_eq!(modified, fs::metadata(format!("{repo}/file")).unwrap().modified().unwrap());
+}
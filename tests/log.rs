@@ -0,0 +1,45 @@
+mod common;
+
+use common::FakeBackend;
+
+use git_conform::core::api::log_all_with;
+use git_conform::core::git::{GitBackend, GitCli};
+
+use std::sync::Arc;
+
+#[tokio::test]
+async fn case_log_all() {
+    let tracking_file = common::fake_tracking();
+
+    // The function executes without errors
+    assert_eq!(log_all_with(&tracking_file, "1.week", None, 2, Arc::new(FakeBackend::default())).await, Ok(()));
+
+    // Repositories whose log couldn't be read are counted
+    let backend = Arc::new(FakeBackend {
+        broken_repo: Some(String::from("/fake/repo1")),
+        ..FakeBackend::default()
+    });
+    assert_eq!(
+        log_all_with(&tracking_file, "1.week", Some("test"), 2, backend).await,
+        Err(String::from("Could not read the log of 1 repositories"))
+    );
+}
+
+#[tokio::test]
+async fn case_log_real() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let repo = format!("{tests_dir}/log_repo");
+    common::init_repo(repo.as_str());
+    common::git(repo.as_str(), &["commit", "--allow-empty", "-m", "Recent work"]);
+    common::git(repo.as_str(), &["switch", "--create", "feature"]);
+    common::git(repo.as_str(), &["commit", "--allow-empty", "-m", "Feature work"]);
+
+    // Commits of every local branch are listed
+    let entries = GitCli.log(repo.as_str(), "1.week", None).await.unwrap();
+    let subjects: Vec<&str> = entries.iter().map(|e| e.subject.as_str()).collect();
+    assert_eq!(subjects, vec!["Feature work", "Recent work"]);
+
+    // The author filter applies
+    assert!(GitCli.log(repo.as_str(), "1.week", Some("nobody-like-this")).await.unwrap().is_empty());
+}
@@ -0,0 +1,205 @@
+use git_conform::core::manifest::{Manifest, ManifestRepo, ManifestRemote, ManifestFormat, ImportSource};
+
+use std::path::Path;
+
+#[test]
+fn case_manifest_format_from_path() {
+    assert_eq!(ManifestFormat::from_path("repos.toml"), Some(ManifestFormat::Toml));
+    assert_eq!(ManifestFormat::from_path("/tmp/repos.json"), Some(ManifestFormat::Json));
+    assert_eq!(ManifestFormat::from_path("repos.yml"), Some(ManifestFormat::Yaml));
+    assert_eq!(ManifestFormat::from_path("repos.yaml"), Some(ManifestFormat::Yaml));
+    assert_eq!(ManifestFormat::from_path("repos.txt"), None);
+    assert_eq!(ManifestFormat::from_path("repos"), None);
+}
+
+#[test]
+fn case_manifest_to_string() {
+    let manifest = Manifest {
+        repos: vec![
+            ManifestRepo {
+                path: String::from("src/app"),
+                branch: Some(String::from("main")),
+                remotes: vec![ManifestRemote {
+                    name: String::from("origin"),
+                    url: String::from("https://example.com/app.git")
+                }],
+                tags: Vec::new()
+            },
+            ManifestRepo {
+                path: String::from("/srv/detached"),
+                branch: None,
+                remotes: Vec::new(),
+                tags: vec![String::from("v2")]
+            }
+        ]
+    };
+
+    let toml = manifest.to_string(ManifestFormat::Toml).unwrap();
+    assert!(toml.contains("[[repos]]\npath = \"src/app\"\nbranch = \"main\""));
+    assert!(toml.contains("[[repos.remotes]]\nname = \"origin\"\nurl = \"https://example.com/app.git\""));
+
+    // Missing branches and tags are left out
+    assert_eq!(toml.matches("branch").count(), 1);
+    assert_eq!(toml.matches("tags").count(), 1);
+
+    let json = manifest.to_string(ManifestFormat::Json).unwrap();
+    assert!(json.contains("\"path\": \"/srv/detached\""));
+
+    let yaml = manifest.to_string(ManifestFormat::Yaml).unwrap();
+    assert!(yaml.starts_with("repos:\n- path: src/app\n"));
+}
+
+#[test]
+fn case_manifest_parse() {
+    let toml = "[[repos]]\npath = \"src/app\"\n\n[[repos.remotes]]\nname = \"origin\"\nurl = \"https://example.com/app.git\"\n";
+    let manifest = Manifest::parse(toml, ManifestFormat::Toml).unwrap();
+
+    // Missing fields take their defaults
+    assert_eq!(manifest.repos.len(), 1);
+    assert_eq!(manifest.repos[0].branch, None);
+    assert!(manifest.repos[0].tags.is_empty());
+    assert_eq!(manifest.repos[0].clone_remote().map(|r| r.url.as_str()), Some("https://example.com/app.git"));
+
+    // Every format reads what it writes
+    for format in [ManifestFormat::Toml, ManifestFormat::Json, ManifestFormat::Yaml] {
+        assert_eq!(Manifest::parse(manifest.to_string(format).unwrap().as_str(), format), Ok(manifest.clone()));
+    }
+
+    assert!(Manifest::parse("repos: 1", ManifestFormat::Yaml).is_err());
+}
+
+#[test]
+fn case_manifest_from_mr() {
+    let mrconfig = "\
+[DEFAULT]
+git_gc = git gc \"$@\"
+
+[src/app]
+checkout = git clone 'https://example.com/app.git' 'app'
+
+[src/lib]
+checkout = git clone --branch develop -o upstream \"https://example.com/lib.git\" lib
+
+[src/svn]
+checkout = svn co 'https://example.com/svn' 'svn'
+";
+
+    let manifest = Manifest::from_source(mrconfig, ImportSource::Mr, Path::new("/home/user"), None).unwrap();
+
+    // Only git repositories are imported, relative to the configuration
+    assert_eq!(manifest.repos, vec![
+        ManifestRepo {
+            path: String::from("/home/user/src/app"),
+            branch: None,
+            remotes: vec![ManifestRemote {
+                name: String::from("origin"),
+                url: String::from("https://example.com/app.git")
+            }],
+            tags: Vec::new()
+        },
+        ManifestRepo {
+            path: String::from("/home/user/src/lib"),
+            branch: Some(String::from("develop")),
+            remotes: vec![ManifestRemote {
+                name: String::from("upstream"),
+                url: String::from("https://example.com/lib.git")
+            }],
+            tags: Vec::new()
+        }
+    ]);
+}
+
+#[test]
+fn case_manifest_from_gita() {
+    let repos_csv = "/home/user/src/app,app,,\n/home/user/src/lib,lib,,\n\n";
+
+    let manifest = Manifest::from_source(repos_csv, ImportSource::Gita, Path::new("/home/user/.config/gita"), None).unwrap();
+
+    let paths: Vec<&str> = manifest.repos.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(paths, vec!["/home/user/src/app", "/home/user/src/lib"]);
+    assert!(manifest.repos.iter().all(|r| r.remotes.is_empty()));
+}
+
+#[test]
+fn case_manifest_from_ghq() {
+    let ghq_list = "github.com/user/app\n/home/user/ghq/gitlab.com/group/lib\n/srv/other\n";
+
+    let manifest = Manifest::from_source(ghq_list, ImportSource::Ghq, Path::new("/home/user/ghq"), None).unwrap();
+
+    // The URLs follow from the paths below the root
+    let repos: Vec<(&str, Option<&str>)> = manifest.repos
+        .iter()
+        .map(|r| (r.path.as_str(), r.clone_remote().map(|r| r.url.as_str())))
+        .collect();
+    assert_eq!(repos, vec![
+        ("/home/user/ghq/github.com/user/app", Some("https://github.com/user/app")),
+        ("/home/user/ghq/gitlab.com/group/lib", Some("https://gitlab.com/group/lib")),
+        ("/srv/other", None)
+    ]);
+}
+
+#[test]
+fn case_manifest_from_repo_manifest() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest>
+  <remote name="aosp" fetch="https://android.googlesource.com/" />
+  <remote name="local" fetch=".." />
+  <default remote="aosp" revision="refs/heads/main" />
+  <project name="platform/build" path="build" />
+  <project name="platform/tools" revision="refs/tags/v1.0" />
+  <project name="platform/pinned" path="pinned" revision="0123456789abcdef0123456789abcdef01234567" />
+  <project name="internal" remote="local" />
+</manifest>"#;
+
+    let manifest_url = Some("https://example.com/platform/manifest");
+    let manifest = Manifest::from_source(xml, ImportSource::RepoManifest, Path::new("/src/aosp"), manifest_url).unwrap();
+
+    assert_eq!(manifest.repos[0], ManifestRepo {
+        path: String::from("/src/aosp/build"),
+        branch: Some(String::from("main")),
+        remotes: vec![ManifestRemote {
+            name: String::from("aosp"),
+            url: String::from("https://android.googlesource.com/platform/build")
+        }],
+        tags: Vec::new()
+    });
+
+    // Paths default to the project names, tags and commits aren't branches
+    assert_eq!(manifest.repos[1].path, "/src/aosp/platform/tools");
+    assert_eq!(manifest.repos[1].tags, vec![String::from("v1.0")]);
+    assert_eq!(manifest.repos[2].branch, None);
+
+    // Relative fetch URLs start at the URL of the manifest repository
+    assert_eq!(manifest.repos[3].remotes, vec![ManifestRemote {
+        name: String::from("local"),
+        url: String::from("https://example.com/internal")
+    }]);
+
+    // They can't be resolved without it
+    assert!(Manifest::from_source(xml, ImportSource::RepoManifest, Path::new("/src/aosp"), None).is_err());
+
+    assert!(Manifest::from_source("<manifest>", ImportSource::RepoManifest, Path::new("/"), None).is_err());
+}
+
+#[test]
+fn case_manifest_from_repo_manifest_relative_urls() {
+    let xml = |fetch| format!(r#"<manifest>
+  <remote name="origin" fetch="{fetch}" />
+  <default remote="origin" />
+  <project name="tools/app" />
+</manifest>"#);
+    let url = |fetch, manifest_url| {
+        Manifest::from_source(xml(fetch).as_str(), ImportSource::RepoManifest, Path::new("/src"), Some(manifest_url))
+            .unwrap()
+            .repos[0]
+            .remotes[0]
+            .url
+            .clone()
+    };
+
+    assert_eq!(url(".", "https://example.com/platform/manifest.git"), "https://example.com/platform/tools/app");
+    assert_eq!(url("../..", "https://example.com/platform/manifest"), "https://example.com/tools/app");
+    assert_eq!(url("../mirror", "https://example.com/platform/manifest/"), "https://example.com/mirror/tools/app");
+    assert_eq!(url("..", "git@example.com:platform/manifest"), "git@example.com:tools/app");
+    assert_eq!(url("..", "/srv/git/platform/manifest"), "/srv/git/tools/app");
+}
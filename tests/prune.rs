@@ -0,0 +1,81 @@
+mod common;
+
+use git_conform::core::api::{prune_branches, DEFAULT_STALE_AGE};
+use git_conform::core::git::{GitBackend, GitCli};
+
+use std::process::{Command, Stdio};
+
+#[tokio::test]
+async fn case_prune_branches() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Repository with a merged, an unmerged and an abandoned branch
+    let repo = format!("{tests_dir}/branches_repo");
+    common::init_repo(repo.as_str());
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["branch", "merged"],
+        vec!["checkout", "-b", "feature"],
+        vec!["commit", "--allow-empty", "-m", "feature"],
+        vec!["checkout", "-b", "old", "main"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+    let status = Command::new("git")
+        .args(["-C", repo.as_str(), "-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(["commit", "--allow-empty", "-m", "old"])
+        .env("GIT_COMMITTER_DATE", "2000-01-01T00:00:00")
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    common::git(repo.as_str(), &["checkout", "main"]);
+
+    assert_eq!(GitCli.default_branch(repo.as_str()).await, Ok(Some(String::from("main"))));
+
+    let branches = async || -> Vec<String> {
+        GitCli.branches(repo.as_str())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect()
+    };
+
+    // The abandoned branch has unmerged commits, it's kept unless forced
+    prune_branches(vec![repo.clone()], DEFAULT_STALE_AGE, false, true).await.unwrap();
+    assert_eq!(branches().await, ["feature", "main", "old"]);
+
+    // Only the recent unmerged branch and the default one are left
+    prune_branches(vec![repo.clone()], DEFAULT_STALE_AGE, true, true).await.unwrap();
+    assert_eq!(branches().await, ["feature", "main"]);
+}
+
+#[tokio::test]
+async fn case_prune_branches_off_default() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Repository with a branch merged into the default one but not into HEAD
+    let repo = format!("{tests_dir}/off_default_repo");
+    common::init_repo(repo.as_str());
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["checkout", "-b", "topic"],
+        vec!["commit", "--allow-empty", "-m", "topic"],
+        vec!["checkout", "main"],
+        vec!["merge", "--ff-only", "topic"],
+        vec!["checkout", "-b", "other", "main~1"]
+    ] {
+        common::git(repo.as_str(), args.as_slice());
+    }
+
+    // The merged branch is deleted without forcing
+    prune_branches(vec![repo.clone()], DEFAULT_STALE_AGE, false, true).await.unwrap();
+    let branches: Vec<String> = GitCli.branches(repo.as_str())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    assert_eq!(branches, ["main", "other"]);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use git_conform::core::api::{push_repos, CheckOptions, FetchMode};
+
+use std::fs;
+
+#[tokio::test]
+async fn case_push() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    let remote = format!("{tests_dir}/push_remote.git");
+    let local = format!("{tests_dir}/push_local");
+    let other = format!("{tests_dir}/push_other");
+    for dir in [&remote, &local, &other] {
+        let _ = fs::remove_dir_all(dir);
+    }
+    fs::create_dir_all(&remote).unwrap();
+    common::git(remote.as_str(), &["init", "--bare", "--initial-branch", "main"]);
+    common::git(tests_dir.as_str(), &["clone", remote.as_str(), local.as_str()]);
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["push", "--set-upstream", "origin", "HEAD:main"],
+        vec!["commit", "--allow-empty", "-m", "second"]
+    ] {
+        common::git(local.as_str(), args.as_slice());
+    }
+
+    let head = |repo: &str, rev: &str| common::git_output(repo, &["rev-parse", rev]);
+
+    // The unpushed commit gets pushed
+    push_repos(vec![local.clone()], &CheckOptions::default(), true).await.unwrap();
+    assert_eq!(head(remote.as_str(), "main"), head(local.as_str(), "HEAD"));
+
+    // Another clone falls behind and commits on its own
+    common::git(tests_dir.as_str(), &["clone", remote.as_str(), other.as_str()]);
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "third"],
+        vec!["push", "origin", "HEAD:main"]
+    ] {
+        common::git(local.as_str(), args.as_slice());
+    }
+    common::git(other.as_str(), &["commit", "--allow-empty", "-m", "diverged"]);
+
+    // Without fetching the branch looks only ahead, the push gets rejected
+    let options = CheckOptions {
+        fetch: FetchMode::Never,
+        ..CheckOptions::default()
+    };
+    let err = push_repos(vec![other.clone()], &options, true).await.unwrap_err();
+    assert!(err.contains("Could not push 1"));
+
+    // Once fetched the branch is known to have diverged and isn't pushed at all
+    push_repos(vec![other.clone()], &CheckOptions::default(), true).await.unwrap();
+    assert_eq!(head(remote.as_str(), "main"), head(local.as_str(), "HEAD"));
+}
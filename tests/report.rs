@@ -0,0 +1,582 @@
+use git_conform::core::api::{FetchTimeouts, DEFAULT_FETCH_TIMEOUT};
+use git_conform::core::git::{FetchOutcome, FileStatus, RepoState, Head, Operation, GrepMatch, LogEntry};
+use git_conform::core::report::{
+    RepoReport,
+    RemoteReport,
+    StatusReport,
+    StatusSummary,
+    BranchReport,
+    Divergence,
+    AheadBehind,
+    Condition,
+    StaleBranch,
+    StaleReason,
+    SyncOutcome,
+    GrepReport,
+    SortKey,
+    table,
+    errors,
+    activity,
+    log,
+    sort
+};
+
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn case_fetch_timeouts() {
+    let mut timeouts = FetchTimeouts::default();
+    let mut configured = FetchTimeouts::default();
+
+    // Without any timeouts the default applies
+    assert_eq!(timeouts.get("origin", &configured), DEFAULT_FETCH_TIMEOUT);
+
+    configured.set(None, Duration::from_secs(30));
+    configured.set(Some(String::from("upstream")), Duration::from_mins(2));
+
+    // Remotes without a dedicated timeout use the global one
+    assert_eq!(timeouts.get("origin", &configured), Duration::from_secs(30));
+    assert_eq!(timeouts.get("upstream", &configured), Duration::from_mins(2));
+
+    // The timeouts given explicitly take precedence over the configured ones
+    timeouts.set(Some(String::from("origin")), Duration::from_secs(5));
+    assert_eq!(timeouts.get("origin", &configured), Duration::from_secs(5));
+    assert_eq!(timeouts.get("upstream", &configured), Duration::from_mins(2));
+
+    timeouts.set(None, Duration::from_secs(20));
+    assert_eq!(timeouts.get("upstream", &configured), Duration::from_secs(20));
+}
+
+#[test]
+fn case_report_fetch_problems() {
+    let mut report = RepoReport {
+        path: String::from("/fake/repo"),
+        remotes: vec![RemoteReport {
+            name: String::from("origin"),
+            fetch: FetchOutcome::Fetched
+        }],
+        ..RepoReport::default()
+    };
+
+    // A successful fetch isn't worth reporting
+    assert!(!report.has_findings(&[]));
+
+    // Failed and timed out fetches are reported
+    report.remotes.push(RemoteReport {
+        name: String::from("upstream"),
+        fetch: FetchOutcome::Failed(String::from("fatal: boom\nmore details"))
+    });
+    report.remotes.push(RemoteReport {
+        name: String::from("mirror"),
+        fetch: FetchOutcome::TimedOut
+    });
+    assert!(report.has_findings(&[]));
+
+    let output = report.to_string();
+    assert!(output.contains("fetch from 'upstream' failed: fatal: boom"));
+    assert!(!output.contains("more details"));
+    assert!(output.contains("fetch from 'mirror' timed out"));
+
+    // The JSON output contains the full error message
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(json.contains(r#""state":"failed","message":"fatal: boom\nmore details""#));
+    assert!(json.contains(r#""state":"timed_out""#));
+}
+
+#[test]
+fn case_report_upstream() {
+    let divergence = |remote: &str, branch: &str, counts: Option<AheadBehind>| Divergence {
+        remote: remote.to_string(),
+        remote_branch: format!("{remote}/{branch}"),
+        counts
+    };
+
+    let report = RepoReport {
+        path: String::from("/fake/repo"),
+        remotes: vec![RemoteReport {
+            name: String::from("origin"),
+            fetch: FetchOutcome::Fetched
+        }],
+        branches: vec![
+            BranchReport {
+                name: String::from("main"),
+                upstream: Some(divergence("origin", "main", Some(AheadBehind { ahead: 0, behind: 0 }))),
+                push: Some(divergence("fork", "main", Some(AheadBehind { ahead: 2, behind: 0 }))),
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("feature"),
+                upstream: None,
+                push: None,
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("old"),
+                upstream: Some(divergence("origin", "old", None)),
+                push: None,
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("synced"),
+                upstream: Some(divergence("origin", "synced", Some(AheadBehind { ahead: 0, behind: 0 }))),
+                push: None,
+                remotes: Vec::new()
+            }
+        ],
+        ..RepoReport::default()
+    };
+
+    let output = report.to_string();
+
+    // Branches are compared with their upstream and push destination
+    assert!(output.contains("2 commit(s) ahead of fork/main (push)"));
+    assert!(!output.contains("origin/main"));
+    assert!(output.contains("no upstream configured"));
+    assert!(output.contains("upstream origin/old is gone"));
+
+    // Branches in sync with their upstream aren't reported
+    assert!(!output.contains("synced"));
+}
+
+#[test]
+fn case_report_only() {
+    let report = RepoReport {
+        path: String::from("/fake/repo"),
+        status: StatusReport::new(vec![FileStatus {
+            path: String::from("new.txt"),
+            code: String::from("??"),
+            renamed_from: None,
+            conflicted: false
+        }]),
+        remotes: vec![RemoteReport {
+            name: String::from("origin"),
+            fetch: FetchOutcome::Fetched
+        }],
+        branches: vec![
+            BranchReport {
+                name: String::from("main"),
+                upstream: Some(Divergence {
+                    remote: String::from("origin"),
+                    remote_branch: String::from("origin/main"),
+                    counts: Some(AheadBehind { ahead: 0, behind: 3 })
+                }),
+                push: None,
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("feature"),
+                upstream: None,
+                push: None,
+                remotes: Vec::new()
+            }
+        ],
+        ..RepoReport::default()
+    };
+
+    assert_eq!(report.conditions(), vec![Condition::Dirty, Condition::Behind, Condition::MissingUpstream]);
+    assert!(report.has_findings(&[Condition::Behind]));
+    assert!(!report.has_findings(&[Condition::Unpushed, Condition::NoRemote]));
+
+    // Only the findings meeting the selected conditions are shown
+    let output = report.findings(&[Condition::Behind]).to_string();
+    assert!(output.contains("3 commit(s) behind origin/main"));
+    assert!(!output.contains("untracked"));
+    assert!(!output.contains("feature"));
+
+    let output = report.findings(&[Condition::Dirty, Condition::MissingUpstream]).to_string();
+    assert!(output.contains("untracked"));
+    assert!(output.contains("no upstream configured"));
+    assert!(!output.contains("origin/main"));
+
+    // Repositories without remotes are reported only as such
+    let report = RepoReport {
+        path: String::from("/fake/local"),
+        ..RepoReport::default()
+    };
+    assert_eq!(report.conditions(), vec![Condition::NoRemote]);
+    assert!(report.findings(&[]).to_string().contains("no remotes configured"));
+}
+
+#[test]
+fn case_status_summary() {
+    let file = |code: &str, renamed_from: Option<&str>, conflicted: bool| FileStatus {
+        path: String::from("file"),
+        code: code.to_string(),
+        renamed_from: renamed_from.map(String::from),
+        conflicted
+    };
+
+    let status = StatusReport::new(vec![
+        file("MM", None, false),
+        file("D ", None, false),
+        file(" D", None, false),
+        file("R ", Some("old"), false),
+        file("UU", None, true),
+        file("??", None, false),
+        file("??", None, false),
+        file("!!", None, false)
+    ]);
+
+    // Files are counted in each category they fall into
+    assert_eq!(status.summary, StatusSummary {
+        staged: 3,
+        modified: 1,
+        deleted: 2,
+        renamed: 1,
+        untracked: 2,
+        conflicted: 1,
+        ignored_large: 1
+    });
+    assert!(status.has_findings());
+    assert_eq!(status.to_string(), "  1 conflicted, 3 staged, 1 modified, 2 deleted, 1 renamed, 2 untracked, 1 ignored but large\n");
+
+    // The alternate form lists every file
+    assert_eq!(format!("{status:#}").lines().count(), 8);
+    assert!(format!("{status:#}").contains("R  old -> file"));
+
+    // Large ignored files alone aren't worth reporting
+    assert!(!StatusReport::new(vec![file("!!", None, false)]).has_findings());
+}
+
+#[test]
+fn case_table() {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    let reports = vec![
+        RepoReport {
+            path: String::from("/home/user/projects/some-long-repository-name"),
+            status: StatusReport::new(vec![FileStatus {
+                path: String::from("file"),
+                code: String::from(" M"),
+                renamed_from: None,
+                conflicted: false
+            }]),
+            state: Some(RepoState {
+                head: Head::Branch(String::from("main")),
+                operation: None,
+                stashes: 2
+            }),
+            branches: vec![BranchReport {
+                name: String::from("main"),
+                upstream: Some(Divergence {
+                    remote: String::from("origin"),
+                    remote_branch: String::from("origin/main"),
+                    counts: Some(AheadBehind { ahead: 3, behind: 1 })
+                }),
+                push: None,
+                remotes: Vec::new()
+            }],
+            remotes: vec![RemoteReport {
+                name: String::from("origin"),
+                fetch: FetchOutcome::Fetched
+            }],
+            last_commit: Some(1_700_000_000),
+            last_commit_author: Some(String::from("test")),
+            last_modified: None,
+            last_fetch: Some(now - 7200),
+            stale_branches: Vec::new(),
+            error: None
+        },
+        RepoReport {
+            path: String::from("/repo"),
+            state: Some(RepoState {
+                head: Head::Detached(String::from("abc1234")),
+                operation: None,
+                stashes: 0
+            }),
+            ..RepoReport::default()
+        }
+    ];
+
+    // One row per repository below the header
+    let output = table(&reports, None);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("/home/user/projects/some-long-repository-name  main"));
+    assert!(lines[1].contains("1M"));
+    assert!(lines[1].contains("↑3 ↓1"));
+    assert!(lines[1].ends_with("2h"));
+    assert!(lines[2].contains("(abc1234)"));
+
+    // Paths get shortened to fit the table in the given width
+    let output = table(&reports, Some(60));
+    assert!(output.lines().all(|l| console::measure_text_width(l) <= 60));
+    assert!(output.contains("…"));
+    assert!(output.contains("name  main"));
+}
+
+#[test]
+fn case_report_errors() {
+    let reports = vec![
+        RepoReport::failed("/fake/broken", String::from("fatal: index file corrupt")),
+        RepoReport {
+            path: String::from("/fake/local"),
+            ..RepoReport::default()
+        }
+    ];
+
+    // Repositories that couldn't be inspected meet no conditions
+    assert!(reports[0].conditions().is_empty());
+
+    // Only the failed repositories are listed along with the reasons
+    let output = errors(&reports);
+    assert!(output.contains("/fake/broken"));
+    assert!(output.contains("fatal: index file corrupt"));
+    assert!(!output.contains("/fake/local"));
+    assert!(errors(&reports[1..]).is_empty());
+}
+
+#[test]
+fn case_sort() {
+    let repo = |path: &str, changes: usize, ahead: u32, last_commit: Option<u64>| RepoReport {
+        path: path.to_string(),
+        status: StatusReport::new((0..changes).map(|n| FileStatus {
+            path: format!("file{n}"),
+            code: String::from("??"),
+            renamed_from: None,
+            conflicted: false
+        }).collect()),
+        branches: vec![BranchReport {
+            name: String::from("main"),
+            upstream: Some(Divergence {
+                remote: String::from("origin"),
+                remote_branch: String::from("origin/main"),
+                counts: Some(AheadBehind { ahead, behind: 0 })
+            }),
+            push: None,
+            remotes: Vec::new()
+        }],
+        last_commit,
+        ..RepoReport::default()
+    };
+    let paths = |reports: &[RepoReport]| reports.iter().map(|r| r.path.clone()).collect::<Vec<String>>();
+
+    let mut reports = vec![
+        repo("/b", 1, 0, Some(200)),
+        repo("/c", 3, 2, None),
+        repo("/a", 1, 5, Some(100))
+    ];
+
+    sort(&mut reports, SortKey::Path);
+    assert_eq!(paths(&reports), ["/a", "/b", "/c"]);
+
+    // Repositories with equal keys keep their order
+    sort(&mut reports, SortKey::Dirty);
+    assert_eq!(paths(&reports), ["/c", "/a", "/b"]);
+
+    sort(&mut reports, SortKey::Ahead);
+    assert_eq!(paths(&reports), ["/a", "/c", "/b"]);
+
+    // Repositories without commits come last
+    sort(&mut reports, SortKey::LastCommit);
+    assert_eq!(paths(&reports), ["/b", "/a", "/c"]);
+}
+
+#[test]
+fn case_report_stale_branches() {
+    let report = RepoReport {
+        path: String::from("/fake/repo"),
+        remotes: vec![RemoteReport {
+            name: String::from("origin"),
+            fetch: FetchOutcome::Fetched
+        }],
+        stale_branches: vec![
+            StaleBranch {
+                name: String::from("done"),
+                reasons: vec![StaleReason::Merged { into: String::from("main") }]
+            },
+            StaleBranch {
+                name: String::from("old"),
+                reasons: vec![
+                    StaleReason::Gone { upstream: String::from("origin/old") },
+                    StaleReason::Inactive { age: 100 * 86400 }
+                ]
+            }
+        ],
+        ..RepoReport::default()
+    };
+
+    assert_eq!(report.conditions(), vec![Condition::StaleBranch]);
+    assert!(report.stale_branches[0].is_merged());
+    assert!(!report.stale_branches[1].is_merged());
+
+    let output = report.to_string();
+    assert!(output.contains("done: merged into main"));
+    assert!(output.contains("old: upstream origin/old is gone, no commits for 100d"));
+
+    // Stale branches are hidden unless selected
+    assert!(!report.findings(&[Condition::Dirty]).to_string().contains("stale"));
+}
+
+#[test]
+fn case_report_activity() {
+    let reports = vec![
+        RepoReport {
+            path: String::from("/fake/repo"),
+            last_commit: Some(100),
+            last_modified: Some(300),
+            last_fetch: Some(200),
+            ..RepoReport::default()
+        },
+        RepoReport {
+            path: String::from("/fake/empty"),
+            ..RepoReport::default()
+        }
+    ];
+
+    // The latest of the commit, modification and fetch counts
+    assert_eq!(reports[0].last_activity(), Some(300));
+    assert_eq!(reports[1].last_activity(), None);
+
+    let output = activity(&reports);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].starts_with("/fake/repo   active "));
+    assert_eq!(lines[1], "/fake/empty  never active");
+}
+
+#[test]
+fn case_fast_forward_target() {
+    let report = |counts: Option<AheadBehind>| RepoReport {
+        path: String::from("/fake/repo"),
+        state: Some(RepoState {
+            head: Head::Branch(String::from("main")),
+            operation: None,
+            stashes: 0
+        }),
+        branches: vec![BranchReport {
+            name: String::from("main"),
+            upstream: Some(Divergence {
+                remote: String::from("origin"),
+                remote_branch: String::from("origin/main"),
+                counts
+            }),
+            push: None,
+            remotes: Vec::new()
+        }],
+        ..RepoReport::default()
+    };
+
+    // Only branches strictly behind their upstream get fast-forwarded
+    let behind = report(Some(AheadBehind { ahead: 0, behind: 3 }));
+    assert_eq!(behind.fast_forward_target(), Ok(("main", "origin/main", 3)));
+    assert_eq!(report(Some(AheadBehind { ahead: 2, behind: 0 })).fast_forward_target(), Err(SyncOutcome::UpToDate));
+    assert!(matches!(report(Some(AheadBehind { ahead: 1, behind: 1 })).fast_forward_target(), Err(SyncOutcome::Skipped(r)) if r.contains("diverged")));
+    assert!(matches!(report(None).fast_forward_target(), Err(SyncOutcome::Skipped(r)) if r.contains("gone")));
+
+    // Dirty repositories and unfinished operations are left alone
+    let mut dirty = behind.clone();
+    dirty.status = StatusReport::new(vec![FileStatus {
+        path: String::from("file"),
+        code: String::from(" M"),
+        renamed_from: None,
+        conflicted: false
+    }]);
+    assert!(matches!(dirty.fast_forward_target(), Err(SyncOutcome::Skipped(r)) if r.contains("uncommitted")));
+
+    let mut rebasing = behind.clone();
+    rebasing.state = Some(RepoState {
+        head: Head::Detached(String::from("abc1234")),
+        operation: Some(Operation::Rebase),
+        stashes: 0
+    });
+    assert_eq!(rebasing.fast_forward_target(), Err(SyncOutcome::Skipped(String::from("rebase in progress"))));
+}
+
+#[test]
+fn case_unpushed_branches() {
+    let divergence = |remote: &str, remote_branch: &str, ahead, behind| Divergence {
+        remote: String::from(remote),
+        remote_branch: String::from(remote_branch),
+        counts: Some(AheadBehind { ahead, behind })
+    };
+
+    let report = RepoReport {
+        path: String::from("/fake/repo"),
+        branches: vec![
+            BranchReport {
+                name: String::from("main"),
+                upstream: Some(divergence("origin", "origin/main", 2, 0)),
+                push: None,
+                remotes: Vec::new()
+            },
+            // Pushed somewhere else than pulled from
+            BranchReport {
+                name: String::from("feature"),
+                upstream: Some(divergence("origin", "origin/main", 5, 0)),
+                push: Some(divergence("fork", "fork/feature", 1, 3)),
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("local"),
+                upstream: Some(divergence(".", "main", 1, 0)),
+                push: None,
+                remotes: Vec::new()
+            },
+            BranchReport {
+                name: String::from("synced"),
+                upstream: Some(divergence("origin", "origin/synced", 0, 4)),
+                push: None,
+                remotes: Vec::new()
+            }
+        ],
+        ..RepoReport::default()
+    };
+
+    let unpushed = report.unpushed_branches();
+    assert_eq!(unpushed.len(), 2);
+
+    assert_eq!(unpushed[0].name, "main");
+    assert_eq!(unpushed[0].remote_ref(), "main");
+    assert!(!unpushed[0].has_diverged());
+
+    assert_eq!(unpushed[1].name, "feature");
+    assert_eq!(unpushed[1].remote, "fork");
+    assert_eq!(unpushed[1].remote_ref(), "feature");
+    assert!(unpushed[1].has_diverged());
+}
+
+#[test]
+fn case_grep_files() {
+    let grep_match = |path: &str, line| GrepMatch {
+        path: String::from(path),
+        line,
+        text: String::from("old_api()")
+    };
+
+    let report = GrepReport {
+        path: String::from("/fake/repo"),
+        matches: vec![
+            grep_match("a.rs", 1),
+            grep_match("a.rs", 7),
+            grep_match("b.rs", 3)
+        ],
+        error: None
+    };
+
+    assert_eq!(report.files(), vec![("a.rs", 2), ("b.rs", 1)]);
+}
+
+#[test]
+fn case_log() {
+    colored::control::set_override(false);
+
+    let entry = |subject: &str, hours| LogEntry {
+        hash: String::from("abc1234"),
+        time: SystemTime::now() - Duration::from_hours(hours),
+        date: String::from("2024-06-01 12:00"),
+        author: String::from("test"),
+        subject: String::from(subject)
+    };
+
+    let logs = vec![
+        (String::from("/repo/a"), vec![entry("third", 1), entry("first", 30)]),
+        (String::from("/repo/bb"), vec![entry("second", 5)])
+    ];
+
+    // The commits of all repositories are merged, newest first
+    assert_eq!(
+        log(&logs),
+        "2024-06-01 12:00  /repo/a   abc1234 third (test)\n\
+         2024-06-01 12:00  /repo/bb  abc1234 second (test)\n\
+         2024-06-01 12:00  /repo/a   abc1234 first (test)\n"
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::implicit_clone)]
+
 mod common;
 
 use git_conform::core::api::{scan_dirs, scan_all};
@@ -7,9 +9,9 @@ use std::path::Path;
 
 use serial_test::serial;
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_hidden() {
+async fn case_scan_dirs_hidden() {
     let (_home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -18,7 +20,7 @@ fn case_scan_dirs_hidden() {
     }
 
     // The function executes without errors
-    assert!(scan_dirs(vec![tests_dir.to_string()], &tracking_file, true).is_ok());
+    assert!(scan_dirs(vec![tests_dir.to_string()], &tracking_file, true).await.is_ok());
 
     // Read the updated tracking file
     let track_file_up = fs::read_to_string(tracking_file.path).unwrap();
@@ -50,9 +52,9 @@ fn case_scan_dirs_hidden() {
     }
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_no_hidden() {
+async fn case_scan_dirs_no_hidden() {
     let (_home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -61,7 +63,7 @@ fn case_scan_dirs_no_hidden() {
     }
 
     // The function executes without errors
-    assert!(scan_dirs(vec![tests_dir.to_string()], &tracking_file, false).is_ok());
+    assert!(scan_dirs(vec![tests_dir.to_string()], &tracking_file, false).await.is_ok());
 
     // Read the updated tracking file
     let track_file_up = fs::read_to_string(tracking_file.path).unwrap();
@@ -94,9 +96,9 @@ fn case_scan_dirs_no_hidden() {
     }
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_non_existent() {
+async fn case_scan_dirs_non_existent() {
     let (_home_dir, tracking_file, _tests_dir) = common::setup().unwrap();
 
     // The function throws an error
@@ -105,12 +107,12 @@ fn case_scan_dirs_non_existent() {
         format!("lvdslns"),
         format!("fjioadbaob")
     ];
-    assert_eq!(scan_dirs(dirs, &tracking_file, true), Err(String::from("Directories validation failed")));
+    assert_eq!(scan_dirs(dirs, &tracking_file, true).await, Err(String::from("Directories validation failed")));
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_files() {
+async fn case_scan_dirs_files() {
     let (_home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // The function throws an error
@@ -118,12 +120,12 @@ fn case_scan_dirs_files() {
     for n in 1..=3 {
         dirs.push(format!("{tests_dir}/file{n}"));
     }
-    assert_eq!(scan_dirs(dirs, &tracking_file, true), Err(String::from("Directories validation failed")));
+    assert_eq!(scan_dirs(dirs, &tracking_file, true).await, Err(String::from("Directories validation failed")));
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_all() {
+async fn case_scan_all() {
     let (home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -132,7 +134,7 @@ fn case_scan_all() {
     }
 
     // The function executes without errors
-    assert!(scan_all(home_dir, &tracking_file, true).is_ok());
+    assert!(scan_all(home_dir, &tracking_file, true).await.is_ok());
 
     // Read the updated tracking file
     let track_file_up = fs::read_to_string(tracking_file.path).unwrap();
@@ -164,9 +166,9 @@ fn case_scan_all() {
     }
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_found_new() {
+async fn case_scan_dirs_found_new() {
     let (_home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -176,7 +178,7 @@ fn case_scan_dirs_found_new() {
 
     // The returned string contains newly found repositories 
     
-    let repos = scan_dirs(vec![tests_dir.to_string()], &tracking_file, true).unwrap();
+    let repos = scan_dirs(vec![tests_dir.to_string()], &tracking_file, true).await.unwrap();
 
     for n in 1..=3 {
         assert!(repos.contains(
@@ -188,9 +190,9 @@ fn case_scan_dirs_found_new() {
     }
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_dirs_found_none() {
+async fn case_scan_dirs_found_none() {
     let (_home_dir, mut tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -201,18 +203,18 @@ fn case_scan_dirs_found_none() {
     let dirs = vec![tests_dir.to_string()];
 
     // Create and populate the tracking file 
-    scan_dirs(dirs.clone(), &tracking_file, true).unwrap();
+    scan_dirs(dirs.clone(), &tracking_file, true).await.unwrap();
 
     // Update the tracking file contents
     tracking_file.contents = fs::read_to_string(&tracking_file.path).unwrap();
 
     // The returned string doesn't contain any repositories
-    assert!(scan_dirs(dirs, &tracking_file, true).unwrap().is_empty());
+    assert!(scan_dirs(dirs, &tracking_file, true).await.unwrap().is_empty());
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_all_found_new() {
+async fn case_scan_all_found_new() {
     let (home_dir, tracking_file, tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -222,7 +224,7 @@ fn case_scan_all_found_new() {
 
     // The returned string contains newly found repositories 
     
-    let repos = scan_all(home_dir, &tracking_file, true).unwrap();
+    let repos = scan_all(home_dir, &tracking_file, true).await.unwrap();
 
     for n in 1..=3 {
         assert!(repos.contains(
@@ -234,9 +236,9 @@ fn case_scan_all_found_new() {
     }
 }
 
-#[test]
+#[tokio::test]
 #[serial]
-fn case_scan_all_found_none() {
+async fn case_scan_all_found_none() {
     let (home_dir, mut tracking_file, _tests_dir) = common::setup().unwrap();
 
     // Remove the tracking file if it already exists
@@ -245,11 +247,11 @@ fn case_scan_all_found_none() {
     }
 
     // Create and populate the tracking file 
-    scan_all(home_dir.clone(), &tracking_file, true).unwrap();
+    scan_all(home_dir.clone(), &tracking_file, true).await.unwrap();
 
     // Update the tracking file contents
     tracking_file.contents = fs::read_to_string(&tracking_file.path).unwrap();
 
     // The returned string doesn't contain any repositories
-    assert!(scan_all(home_dir, &tracking_file, true).unwrap().is_empty());
+    assert!(scan_all(home_dir, &tracking_file, true).await.unwrap().is_empty());
 }
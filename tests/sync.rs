@@ -0,0 +1,43 @@
+mod common;
+
+use git_conform::core::api::{sync_repos, CheckOptions};
+
+use std::fs;
+
+#[tokio::test]
+async fn case_sync() {
+    let (_home_dir, _track_file_path, tests_dir) = common::setup().unwrap();
+
+    // Two clones of the same remote, one of them gets behind
+    let remote = format!("{tests_dir}/sync_remote.git");
+    let ahead = format!("{tests_dir}/sync_ahead");
+    let behind = format!("{tests_dir}/sync_behind");
+    for dir in [&remote, &ahead, &behind] {
+        let _ = fs::remove_dir_all(dir);
+    }
+    fs::create_dir_all(&remote).unwrap();
+    common::git(remote.as_str(), &["init", "--bare", "--initial-branch", "main"]);
+    common::git(tests_dir.as_str(), &["clone", remote.as_str(), ahead.as_str()]);
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "first"],
+        vec!["push", "origin", "HEAD:main"]
+    ] {
+        common::git(ahead.as_str(), args.as_slice());
+    }
+    common::git(tests_dir.as_str(), &["clone", remote.as_str(), behind.as_str()]);
+    for args in [
+        vec!["commit", "--allow-empty", "-m", "second"],
+        vec!["push", "origin", "HEAD:main"]
+    ] {
+        common::git(ahead.as_str(), args.as_slice());
+    }
+
+    let head = |repo: &str| common::git_output(repo, &["rev-parse", "HEAD"]);
+
+    // A dry run changes nothing
+    sync_repos(vec![behind.clone()], &CheckOptions::default(), true).await.unwrap();
+    assert_ne!(head(behind.as_str()), head(ahead.as_str()));
+
+    sync_repos(vec![behind.clone()], &CheckOptions::default(), false).await.unwrap();
+    assert_eq!(head(behind.as_str()), head(ahead.as_str()));
+}
@@ -0,0 +1,26 @@
+use git_conform::utils::{parse_duration, format_age};
+
+use std::time::Duration;
+
+#[test]
+fn case_parse_duration() {
+    // Valid durations are parsed
+    assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+    assert_eq!(parse_duration("30m"), Ok(Duration::from_mins(30)));
+    assert_eq!(parse_duration("12h"), Ok(Duration::from_hours(12)));
+    assert_eq!(parse_duration("90d"), Ok(Duration::from_hours(90 * 24)));
+    assert_eq!(parse_duration("2w"), Ok(Duration::from_hours(14 * 24)));
+
+    // Invalid durations throw an error
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("d").is_err());
+    assert!(parse_duration("5y").is_err());
+}
+
+#[test]
+fn case_format_age() {
+    assert_eq!(format_age(Duration::from_secs(5)), "5s");
+    assert_eq!(format_age(Duration::from_mins(5)), "5m");
+    assert_eq!(format_age(Duration::from_hours(5)), "5h");
+    assert_eq!(format_age(Duration::from_hours(5 * 24)), "5d");
+}